@@ -31,11 +31,11 @@ done
 
 echo ""
 echo "=== Running tests ==="
-cargo test --release --test example --test tests -- --nocapture
+cargo test --release --all-features --test example --test tests -- --nocapture
 echo ""
 
 echo "=== Running speed tests ==="
-for test in send_stream send_standard recv_stream recv_standard both_stream; do
+for test in send_stream send_copy_stream send_standard send_copy_standard recv_stream recv_standard recv_credit_stream recv_credit_standard both_stream; do
     echo "--- $test ---"
     cargo test --release --test speed -- "$test" --nocapture
     echo ""
@@ -0,0 +1,43 @@
+//! Aborting WebSocket connections.
+
+use web_sys::{AbortController, AbortSignal};
+
+/// A handle for aborting a WebSocket from Rust.
+///
+/// Pass its [signal](Self::signal) to [WebSocketBuilder::set_abort_signal](crate::WebSocketBuilder::set_abort_signal)
+/// and call [abort](Self::abort) to cancel a pending connection attempt
+/// and close the WebSocket once it has been established.
+///
+/// Cloning the handle yields a handle controlling the same signal.
+#[derive(Debug, Clone)]
+pub struct AbortHandle(AbortController);
+
+impl AbortHandle {
+    /// Creates a new abort handle.
+    pub fn new() -> Self {
+        Self(AbortController::new().expect("AbortController not supported"))
+    }
+
+    /// The abort signal controlled by this handle.
+    pub fn signal(&self) -> AbortSignal {
+        self.0.signal()
+    }
+
+    /// Aborts.
+    ///
+    /// This has no effect if already aborted.
+    pub fn abort(&self) {
+        self.0.abort();
+    }
+
+    /// Whether [abort](Self::abort) has been called.
+    pub fn is_aborted(&self) -> bool {
+        self.0.signal().aborted()
+    }
+}
+
+impl Default for AbortHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,172 @@
+//! Incremental blob reading.
+
+use futures_core::Stream;
+use futures_util::FutureExt;
+use js_sys::Uint8Array;
+use std::{
+    fmt, io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::io::{AsyncRead, ReadBuf};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Blob;
+
+use crate::WebSocketError;
+
+const DEFAULT_CHUNK_SIZE: usize = 1_048_576;
+
+/// Reads a [Blob] incrementally, one slice at a time.
+///
+/// This keeps memory usage bounded when processing large binary messages received
+/// as [blobs](crate::WebSocketBuilder::set_receive_blobs), since only the slice being
+/// read is loaded into memory.
+///
+/// The data can be read using [AsyncRead], which copies each slice directly into
+/// the read buffer, or as a [Stream] of chunks.
+pub struct BlobReader {
+    blob: Blob,
+    size: u64,
+    requested: u64,
+    pos: u64,
+    chunk_size: usize,
+    reading: Option<JsFuture>,
+    chunk: Option<Uint8Array>,
+}
+
+impl fmt::Debug for BlobReader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BlobReader")
+            .field("size", &self.size)
+            .field("pos", &self.pos)
+            .field("chunk_size", &self.chunk_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BlobReader {
+    /// Creates a reader for the specified blob.
+    pub fn new(blob: Blob) -> Self {
+        Self {
+            size: blob.size() as u64,
+            blob,
+            requested: 0,
+            pos: 0,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            reading: None,
+            chunk: None,
+        }
+    }
+
+    /// Creates a reader for the specified byte range of the blob.
+    pub fn with_range(blob: &Blob, start: u64, end: u64) -> Result<Self, WebSocketError> {
+        let slice =
+            blob.slice_with_f64_and_f64(start as f64, end as f64).map_err(|err| WebSocketError::js(&err))?;
+        Ok(Self::new(slice))
+    }
+
+    /// Sets the size in bytes of the slices read from the blob.
+    ///
+    /// This is also the maximum size of the chunks yielded by the [Stream].
+    ///
+    /// By default, 1 MiB is used.
+    ///
+    /// # Panics
+    /// Panics if the chunk size is zero.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        assert!(chunk_size > 0, "chunk size must not be zero");
+        self.chunk_size = chunk_size;
+    }
+
+    /// The blob being read.
+    pub fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    /// Size of the blob in bytes.
+    pub fn len(&self) -> u64 {
+        self.size
+    }
+
+    /// Whether the blob is empty.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Number of bytes that have been read.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Whether all data has been read.
+    pub(crate) fn is_done(&self) -> bool {
+        self.pos >= self.size
+    }
+
+    /// Polls for the next at most `max` bytes of data.
+    ///
+    /// Returns `None` once all data has been read.
+    fn poll_read_js(&mut self, cx: &mut Context, max: usize) -> Poll<Result<Option<Uint8Array>, WebSocketError>> {
+        loop {
+            if let Some(chunk) = self.chunk.take() {
+                let len = chunk.length();
+                let n = len.min(u32::try_from(max).unwrap_or(u32::MAX));
+                if n < len {
+                    self.chunk = Some(chunk.subarray(n, len));
+                }
+                self.pos += u64::from(n);
+                return Poll::Ready(Ok(Some(chunk.subarray(0, n))));
+            }
+
+            if let Some(reading) = &mut self.reading {
+                let res = ready!(reading.poll_unpin(cx));
+                self.reading = None;
+                let buffer = res.map_err(|err| WebSocketError::js(&err))?;
+                self.chunk = Some(Uint8Array::new(&buffer));
+                continue;
+            }
+
+            if self.requested >= self.size {
+                return Poll::Ready(Ok(None));
+            }
+
+            let end = self.size.min(self.requested + self.chunk_size as u64);
+            let slice = self
+                .blob
+                .slice_with_f64_and_f64(self.requested as f64, end as f64)
+                .map_err(|err| WebSocketError::js(&err))?;
+            self.reading = Some(JsFuture::from(slice.array_buffer()));
+            self.requested = end;
+        }
+    }
+}
+
+impl Stream for BlobReader {
+    type Item = Result<Vec<u8>, WebSocketError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let chunk_size = self.chunk_size;
+        let res = ready!(self.poll_read_js(cx, chunk_size));
+        Poll::Ready(res.transpose().map(|res| res.map(|chunk| chunk.to_vec())))
+    }
+}
+
+impl AsyncRead for BlobReader {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        if let Some(chunk) = ready!(self.poll_read_js(cx, buf.remaining()))? {
+            let n = chunk.length() as usize;
+            // SAFETY: the copied bytes are initialized before being marked as such.
+            unsafe {
+                chunk.copy_to_uninit(&mut buf.unfilled_mut()[..n]);
+                buf.assume_init(n);
+            }
+            buf.advance(n);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
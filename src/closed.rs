@@ -4,21 +4,152 @@ use std::{
     fmt,
     future::Future,
     pin::Pin,
+    rc::{Rc, Weak},
     task::{Context, Poll},
 };
 
 use futures_util::FutureExt;
+use tokio::sync::watch;
+
+use crate::{
+    standard,
+    state::{publish_state, ConnectionState},
+    stream,
+};
 
 /// Reason for why a WebSocket connection is closed.
 #[derive(Debug, Clone)]
 pub struct ClosedReason {
     /// A number representing the closing code.
+    ///
+    /// See [CloseCode] for codes that are only reported locally.
     pub code: CloseCode,
     /// A string representing a human-readable description of
     /// the reason why the socket connection was closed.
     pub reason: String,
-    /// Indicates whether or not the connection was cleanly closed
+    /// Indicates whether or not the connection was cleanly closed,
+    /// i.e. the close handshake has been completed.
     pub was_clean: bool,
+    /// Which side initiated closing the connection.
+    pub initiator: CloseInitiator,
+}
+
+impl ClosedReason {
+    /// Close reason reported by the browser.
+    ///
+    /// The `code` is normalized to [CloseCode::AbnormalClosure] if the
+    /// connection has not been closed cleanly.
+    pub(crate) fn from_browser(code: u16, reason: String, was_clean: bool, closed_locally: bool) -> Self {
+        Self {
+            code: if was_clean { CloseCode::from(code) } else { CloseCode::AbnormalClosure },
+            reason,
+            was_clean,
+            initiator: match (closed_locally, was_clean) {
+                (true, _) => CloseInitiator::Local,
+                (false, true) => CloseInitiator::Remote,
+                (false, false) => CloseInitiator::Browser,
+            },
+        }
+    }
+
+    /// Close reason used when the WebSocket has been closed for an unknown reason.
+    pub(crate) fn unknown() -> Self {
+        Self {
+            code: CloseCode::AbnormalClosure,
+            reason: String::new(),
+            was_clean: false,
+            initiator: CloseInitiator::Browser,
+        }
+    }
+
+    /// Close reason used when the WebSocket has been dropped before it was closed.
+    pub(crate) fn dropped() -> Self {
+        Self {
+            code: CloseCode::AbnormalClosure,
+            reason: "WebSocket dropped".to_string(),
+            was_clean: false,
+            initiator: CloseInitiator::Local,
+        }
+    }
+
+    /// Close reason used when the close handshake did not complete in time.
+    pub(crate) fn close_timed_out() -> Self {
+        Self {
+            code: CloseCode::AbnormalClosure,
+            reason: "close handshake timed out".to_string(),
+            was_clean: false,
+            initiator: CloseInitiator::Local,
+        }
+    }
+
+    /// Close reason used when no heartbeat pong was received in time.
+    pub(crate) fn heartbeat_timed_out() -> Self {
+        Self {
+            code: CloseCode::AbnormalClosure,
+            reason: "heartbeat timed out".to_string(),
+            was_clean: false,
+            initiator: CloseInitiator::Local,
+        }
+    }
+
+    /// Close reason used when the receive buffer overflowed.
+    ///
+    /// The close code is only reported locally, see [CloseCode].
+    pub(crate) fn receive_overflow() -> Self {
+        Self {
+            code: CloseCode::MessageTooBig,
+            reason: "receive buffer overflow".to_string(),
+            was_clean: false,
+            initiator: CloseInitiator::LocalOverflow,
+        }
+    }
+}
+
+/// The side that initiated closing a WebSocket connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CloseInitiator {
+    /// Closed by this endpoint, either explicitly, by dropping it
+    /// or because of a local failure, such as a timeout.
+    Local,
+    /// Closed by this endpoint because the receive buffer overflowed.
+    ///
+    /// The close code is [CloseCode::MessageTooBig], which is only reported locally.
+    LocalOverflow,
+    /// Closed by the server, which initiated the close handshake.
+    Remote,
+    /// Closed by the browser without a close handshake,
+    /// for example because the connection was lost.
+    Browser,
+}
+
+impl fmt::Display for CloseInitiator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::LocalOverflow => write!(f, "local overflow"),
+            Self::Remote => write!(f, "remote"),
+            Self::Browser => write!(f, "browser"),
+        }
+    }
+}
+
+/// Publishes the close reason, unless one has already been published,
+/// and advances the connection state to [ConnectionState::Closed].
+///
+/// Returns whether the close reason was published.
+pub(crate) fn publish_closed(
+    closed_tx: &watch::Sender<Option<ClosedReason>>, state_tx: &watch::Sender<ConnectionState>,
+    reason: ClosedReason,
+) -> bool {
+    let published = closed_tx.send_if_modified(|current| match current {
+        Some(_) => false,
+        None => {
+            *current = Some(reason);
+            true
+        }
+    });
+    publish_state(state_tx, ConnectionState::Closed);
+    published
 }
 
 impl fmt::Display for ClosedReason {
@@ -31,7 +162,14 @@ impl fmt::Display for ClosedReason {
     }
 }
 
+impl std::error::Error for ClosedReason {}
+
 /// A close code indicating why a WebSocket connection was closed.
+///
+/// When the connection is closed locally because a receive limit was violated
+/// or the receive buffer overflowed, the close code describes the cause but is only
+/// reported locally. Browsers only allow sending close code 1000 or 3000-4999,
+/// thus the server sees a close without status code.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u16)]
 pub enum CloseCode {
@@ -151,11 +289,44 @@ impl CloseCode {
             _ => false,
         }
     }
+
+    /// Whether the close code indicates a temporary condition, i.e.
+    /// reestablishing the connection may succeed.
+    ///
+    /// This is the case for [CloseCode::GoingAway], [CloseCode::AbnormalClosure],
+    /// [CloseCode::InternalError], [CloseCode::ServiceRestart], [CloseCode::TryAgainLater]
+    /// and [CloseCode::BadGateway].
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::GoingAway
+                | Self::AbnormalClosure
+                | Self::InternalError
+                | Self::ServiceRestart
+                | Self::TryAgainLater
+                | Self::BadGateway
+        )
+    }
 }
 
 /// A future that resolves once a WebSocket has been closed.
 pub struct Closed(pub(crate) Pin<Box<dyn Future<Output = ClosedReason>>>);
 
+impl Closed {
+    /// Resolves once a close reason has been published into the watch channel.
+    pub(crate) fn new(mut closed_rx: watch::Receiver<Option<ClosedReason>>) -> Self {
+        Self(
+            async move {
+                match closed_rx.wait_for(|c| c.is_some()).await {
+                    Ok(reason) => reason.clone().unwrap(),
+                    Err(_) => ClosedReason::dropped(),
+                }
+            }
+            .boxed_local(),
+        )
+    }
+}
+
 impl fmt::Debug for Closed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("Closed").finish()
@@ -168,3 +339,89 @@ impl Future for Closed {
         self.0.poll_unpin(cx)
     }
 }
+
+/// A cloneable handle for closing a WebSocket.
+///
+/// It can be obtained from a [WebSocket](crate::WebSocket), [WebSocketSender](crate::WebSocketSender)
+/// or [WebSocketReceiver](crate::WebSocketReceiver) and remains usable after splitting, for example
+/// to close the WebSocket from a receive task that detected a protocol violation.
+///
+/// Closing is idempotent: only the first close takes effect.
+///
+/// A handle does not keep the WebSocket alive: once the WebSocket or both of its halves
+/// have been dropped, it is closed and the handle can no longer close it.
+#[derive(Clone)]
+pub struct CloseHandle {
+    guard: CloseHandleInner,
+    closed_rx: watch::Receiver<Option<ClosedReason>>,
+}
+
+#[derive(Clone)]
+enum CloseHandleInner {
+    Stream(Weak<stream::Guard>),
+    Standard(Weak<standard::Guard>),
+}
+
+impl fmt::Debug for CloseHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CloseHandle").field("closed_locally", &self.is_closed_locally()).finish()
+    }
+}
+
+impl CloseHandle {
+    pub(crate) fn stream(guard: &Rc<stream::Guard>) -> Self {
+        Self { guard: CloseHandleInner::Stream(Rc::downgrade(guard)), closed_rx: guard.closed_rx() }
+    }
+
+    pub(crate) fn standard(guard: &Rc<standard::Guard>) -> Self {
+        Self { guard: CloseHandleInner::Standard(Rc::downgrade(guard)), closed_rx: guard.closed_rx() }
+    }
+
+    /// Closes the WebSocket.
+    ///
+    /// Returns whether the WebSocket was closed by this call, i.e. `false` if it
+    /// has already been closed locally or remotely.
+    pub fn close(&self) -> bool {
+        self.close_with_reason(CloseCode::NormalClosure, "")
+    }
+
+    /// Closes the WebSocket with the specified close code and reason.
+    ///
+    /// Returns whether the WebSocket was closed by this call, i.e. `false` if it
+    /// has already been closed locally or remotely or has been dropped.
+    ///
+    /// ## Panics
+    /// Panics if the close code is neither [CloseCode::NormalClosure] nor
+    /// [CloseCode::Other] with a value between 3000 and 4999.
+    #[track_caller]
+    pub fn close_with_reason(&self, code: CloseCode, reason: &str) -> bool {
+        if !code.is_valid() {
+            panic!("WebSocket close code {code} is invalid");
+        }
+
+        match &self.guard {
+            CloseHandleInner::Stream(guard) => {
+                guard.upgrade().is_some_and(|guard| guard.close_with_reason(code.into(), reason))
+            }
+            CloseHandleInner::Standard(guard) => {
+                guard.upgrade().is_some_and(|guard| guard.close_with_reason(code.into(), reason))
+            }
+        }
+    }
+
+    /// Whether closing of the WebSocket was initiated locally.
+    ///
+    /// This is the case if it has been closed using any handle,
+    /// because of a local failure, such as a timeout, or by dropping it.
+    pub fn is_closed_locally(&self) -> bool {
+        match &self.guard {
+            CloseHandleInner::Stream(guard) => guard.upgrade().is_none_or(|guard| guard.is_closed_locally()),
+            CloseHandleInner::Standard(guard) => guard.upgrade().is_none_or(|guard| guard.is_closed_locally()),
+        }
+    }
+
+    /// Returns a future that resolves when the WebSocket is closed.
+    pub fn closed(&self) -> Closed {
+        Closed::new(self.closed_rx.clone())
+    }
+}
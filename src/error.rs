@@ -0,0 +1,111 @@
+//! WebSocket errors.
+
+use js_sys::Reflect;
+use std::{error::Error, fmt, io, io::ErrorKind};
+use wasm_bindgen::prelude::*;
+
+use crate::{util::js_err_msg, ClosedReason, Interface};
+
+/// An error that occurred on a WebSocket.
+///
+/// It can be converted into an [io::Error] with a corresponding [kind](Self::kind).
+/// The [io::Error] wraps the [ClosedReason] for [WebSocketError::Closed] and
+/// [WebSocketError::ReceiveOverflow], and the [WebSocketError] itself otherwise.
+/// Both can be retrieved using
+/// [downcast_ref](https://doc.rust-lang.org/std/error/trait.Error.html#method.downcast_ref) on [io::Error::get_ref].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum WebSocketError {
+    /// The connection could not be established.
+    ConnectFailed(String),
+    /// The WebSocket has been closed.
+    Closed(ClosedReason),
+    /// The receive buffer overflowed and thus the WebSocket has been closed.
+    ReceiveOverflow(ClosedReason),
+    /// The WebSocket URL is invalid.
+    InvalidUrl(String),
+    /// The WebSocket interface is not supported by the JavaScript runtime.
+    Unsupported(Interface),
+    /// The operation did not complete in time.
+    TimedOut,
+    /// The operation was aborted.
+    Aborted,
+    /// The provided buffer is too small for the received message.
+    ///
+    /// The message has not been removed and is returned by the next receive.
+    BufferTooSmall {
+        /// Size of the message in bytes.
+        required: usize,
+    },
+    /// A JavaScript exception occurred.
+    Js {
+        /// Name of the exception, for example `TypeError`.
+        name: String,
+        /// Exception message.
+        message: String,
+        /// Stack trace, if available.
+        stack: Option<String>,
+    },
+}
+
+impl WebSocketError {
+    /// Creates an error from a JavaScript exception.
+    pub(crate) fn js(value: &JsValue) -> Self {
+        match value.dyn_ref::<js_sys::Error>() {
+            Some(err) => Self::Js {
+                name: err.name().into(),
+                message: err.message().into(),
+                stack: Reflect::get(err, &JsValue::from_str("stack")).ok().and_then(|stack| stack.as_string()),
+            },
+            None => Self::Js {
+                name: "Error".to_string(),
+                message: js_err_msg(value).unwrap_or_default(),
+                stack: None,
+            },
+        }
+    }
+
+    /// The [io::ErrorKind] used when converting into an [io::Error].
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::ConnectFailed(_) => ErrorKind::ConnectionRefused,
+            Self::Closed(_) | Self::ReceiveOverflow(_) => ErrorKind::ConnectionReset,
+            Self::InvalidUrl(_) | Self::BufferTooSmall { .. } => ErrorKind::InvalidInput,
+            Self::Unsupported(_) => ErrorKind::Unsupported,
+            Self::TimedOut => ErrorKind::TimedOut,
+            Self::Aborted => ErrorKind::Interrupted,
+            Self::Js { .. } => ErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for WebSocketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ConnectFailed(msg) if msg.is_empty() => write!(f, "WebSocket connect failed"),
+            Self::ConnectFailed(msg) => write!(f, "WebSocket connect failed: {msg}"),
+            Self::Closed(reason) => write!(f, "WebSocket closed: {reason}"),
+            Self::ReceiveOverflow(reason) => write!(f, "WebSocket receive buffer overflow: {reason}"),
+            Self::InvalidUrl(msg) => write!(f, "invalid WebSocket URL: {msg}"),
+            Self::Unsupported(Interface::Stream) => write!(f, "WebSocketStream not supported"),
+            Self::Unsupported(Interface::Standard) => write!(f, "WebSocket not supported"),
+            Self::TimedOut => write!(f, "WebSocket timed out"),
+            Self::Aborted => write!(f, "WebSocket aborted"),
+            Self::BufferTooSmall { required } => write!(f, "buffer too small for message of {required} bytes"),
+            Self::Js { name, message, .. } => write!(f, "{name}: {message}"),
+        }
+    }
+}
+
+impl Error for WebSocketError {}
+
+impl From<WebSocketError> for io::Error {
+    fn from(err: WebSocketError) -> Self {
+        match err {
+            WebSocketError::Closed(reason) | WebSocketError::ReceiveOverflow(reason) => {
+                io::Error::new(ErrorKind::ConnectionReset, reason)
+            }
+            err => io::Error::new(err.kind(), err),
+        }
+    }
+}
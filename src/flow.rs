@@ -0,0 +1,99 @@
+//! Cooperative receive flow control.
+
+use std::cell::Cell;
+
+/// Cooperative, credit-based flow control for received messages.
+///
+/// The [standard WebSocket interface](crate::Interface::Standard) provides no receive
+/// backpressure: the browser delivers all messages as fast as the server sends them.
+/// Flow control lets the server know how much data the application is willing to receive.
+/// It must be implemented by the server.
+///
+/// The client grants credits to the server by sending text messages of the form
+/// `FLOW-CREDIT <bytes> <messages>`. After the connection has been established, credits
+/// for the whole window are granted. Further credits are granted as the application
+/// receives messages.
+///
+/// The server may send a message while it has a positive byte credit and at least one
+/// message credit. The size of each sent message is deducted from the byte credit,
+/// which thus may become negative, and each sent message consumes one message credit.
+/// Consequently, the receive buffer must be larger than the window by at least the
+/// maximum message size.
+///
+/// All messages sent by the server consume credits, including heartbeat pongs.
+/// Credits for messages dropped by the [receive overflow policy](crate::ReceiveOverflowPolicy)
+/// are granted again, as if the application had received them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowControl {
+    window_bytes: usize,
+    window_messages: usize,
+}
+
+impl FlowControl {
+    /// Prefix of the text messages granting credits.
+    pub const CREDIT_PREFIX: &'static str = "FLOW-CREDIT ";
+
+    /// Creates a new flow control configuration.
+    ///
+    /// At most `window_bytes` bytes (plus the size of one message) and `window_messages`
+    /// messages are in flight or buffered and not yet received by the application.
+    ///
+    /// # Panics
+    /// Panics if either window is zero.
+    pub fn new(window_bytes: usize, window_messages: usize) -> Self {
+        assert!(window_bytes > 0 && window_messages > 0, "flow control window must not be empty");
+        Self { window_bytes, window_messages }
+    }
+
+    /// Window size in bytes.
+    pub fn window_bytes(&self) -> usize {
+        self.window_bytes
+    }
+
+    /// Window size in messages.
+    pub fn window_messages(&self) -> usize {
+        self.window_messages
+    }
+}
+
+/// Tracks received messages and decides when to grant further credits.
+pub(crate) struct Credits {
+    flow_control: FlowControl,
+    bytes: Cell<usize>,
+    messages: Cell<usize>,
+}
+
+impl Credits {
+    pub fn new(flow_control: FlowControl) -> Self {
+        Self { flow_control, bytes: Cell::new(0), messages: Cell::new(0) }
+    }
+
+    /// Message granting credits for the whole window.
+    pub fn initial(&self) -> String {
+        grant(self.flow_control.window_bytes, self.flow_control.window_messages)
+    }
+
+    /// Accounts for a message of the specified size that has been received by the application.
+    ///
+    /// Returns the message granting credits once half of the window has been consumed.
+    pub fn consumed(&self, len: usize) -> Option<String> {
+        let bytes = self.bytes.get() + len;
+        let messages = self.messages.get() + 1;
+
+        if bytes >= self.flow_control.window_bytes.div_ceil(2)
+            || messages >= self.flow_control.window_messages.div_ceil(2)
+        {
+            self.bytes.set(0);
+            self.messages.set(0);
+            Some(grant(bytes, messages))
+        } else {
+            self.bytes.set(bytes);
+            self.messages.set(messages);
+            None
+        }
+    }
+}
+
+fn grant(bytes: usize, messages: usize) -> String {
+    format!("{}{bytes} {messages}", FlowControl::CREDIT_PREFIX)
+}
@@ -0,0 +1,147 @@
+//! Application-level heartbeat.
+
+use js_sys::Uint8Array;
+use std::{cell::Cell, fmt, rc::Rc, time::Duration};
+use tokio::sync::watch;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    util::{now, sleep},
+    ClosedReason, Msg,
+};
+
+/// Application-level heartbeat for detecting dead peers.
+///
+/// Browsers handle WebSocket ping and pong frames internally and do not expose them.
+/// Thus a half-open connection, for example caused by a sleeping laptop or an expired
+/// NAT mapping, looks alive until the underlying TCP connection times out, which may
+/// take several minutes.
+///
+/// A heartbeat periodically sends a ping message and expects the server to answer
+/// with a pong message within a timeout. Received pong messages are identified using
+/// a predicate and are not returned when receiving from the WebSocket.
+///
+/// If no pong is received in time, the peer is considered dead: the WebSocket is closed,
+/// [WebSocket::closed](crate::WebSocket::closed) resolves with
+/// [CloseCode::AbnormalClosure](crate::CloseCode::AbnormalClosure) and receiving fails with
+/// [WebSocketError::TimedOut](crate::WebSocketError::TimedOut).
+///
+/// When using the [stream-based WebSocket interface](crate::Interface::Stream), pongs are
+/// only noticed while the application receives from the WebSocket. A missing pong is thus
+/// only treated as a dead peer while the application is waiting for incoming messages.
+#[derive(Clone)]
+pub struct Heartbeat {
+    interval: Duration,
+    timeout: Duration,
+    ping: Msg,
+    is_pong: Rc<dyn Fn(&Msg) -> bool>,
+}
+
+impl fmt::Debug for Heartbeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Heartbeat")
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("ping", &self.ping)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Heartbeat {
+    /// Creates a new heartbeat.
+    ///
+    /// The `ping` message is sent after each `interval` and a message for which
+    /// `is_pong` returns `true` must be received within `timeout` after that.
+    pub fn new(
+        interval: Duration, timeout: Duration, ping: Msg, is_pong: impl Fn(&Msg) -> bool + 'static,
+    ) -> Self {
+        Self { interval, timeout, ping, is_pong: Rc::new(is_pong) }
+    }
+
+    /// Interval between pings.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Maximum time for receiving a pong after a ping has been sent.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Ping message.
+    pub fn ping(&self) -> &Msg {
+        &self.ping
+    }
+}
+
+/// Monitors the liveness of a peer using a heartbeat.
+pub(crate) struct Monitor {
+    heartbeat: Heartbeat,
+    last_pong: Cell<f64>,
+    receiving: Cell<bool>,
+    closed_rx: watch::Receiver<Option<ClosedReason>>,
+}
+
+impl Monitor {
+    /// Creates a new monitor.
+    ///
+    /// `receiving` specifies whether received messages are noticed
+    /// without the application reading them.
+    pub fn new(heartbeat: Heartbeat, receiving: bool, closed_rx: watch::Receiver<Option<ClosedReason>>) -> Self {
+        Self { heartbeat, last_pong: Cell::new(0.), receiving: Cell::new(receiving), closed_rx }
+    }
+
+    /// Whether the message is a pong, in which case its arrival is recorded.
+    pub fn is_pong(&self, msg: &Msg) -> bool {
+        if (self.heartbeat.is_pong)(msg) {
+            self.last_pong.set(now());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets whether the receiver is waiting for incoming messages.
+    ///
+    /// A missing pong only indicates a dead peer while the receiver is waiting,
+    /// since otherwise the pong might be queued but not yet read.
+    pub fn set_receiving(&self, receiving: bool) {
+        self.receiving.set(receiving);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed_rx.borrow().is_some() || self.closed_rx.has_changed().is_err()
+    }
+
+    /// Periodically sends pings using `send_ping` until the WebSocket is closed
+    /// or the peer is considered dead.
+    ///
+    /// Returns whether the peer is dead.
+    pub async fn run(&self, send_ping: impl Fn(&JsValue)) -> bool {
+        let ping = match &self.heartbeat.ping {
+            Msg::Text(text) => JsValue::from_str(text),
+            Msg::Binary(vec) => Uint8Array::from(vec.as_slice()).into(),
+        };
+
+        loop {
+            sleep(self.heartbeat.interval).await;
+            if self.is_closed() {
+                return false;
+            }
+
+            let sent = now();
+            send_ping(&ping);
+            sleep(self.heartbeat.timeout).await;
+
+            while self.last_pong.get() < sent {
+                if self.is_closed() {
+                    return false;
+                }
+                if self.receiving.get() {
+                    return true;
+                }
+                sleep(self.heartbeat.timeout).await;
+            }
+        }
+    }
+}
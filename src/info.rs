@@ -0,0 +1,71 @@
+//! Connection information.
+
+use std::time::{Duration, SystemTime};
+
+use crate::{util::unix_time, Interface};
+
+/// Information about an established WebSocket connection.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub(crate) url: String,
+    pub(crate) protocol: String,
+    pub(crate) extensions: String,
+    pub(crate) interface: Interface,
+    pub(crate) connect_started: f64,
+    pub(crate) opened: f64,
+}
+
+impl ConnectionInfo {
+    /// The absolute URL of the WebSocket server, as resolved by the browser.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The sub-protocol selected by the server.
+    ///
+    /// Empty if no sub-protocol has been negotiated.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// The extensions selected by the server, as sent in the `Sec-WebSocket-Extensions` header.
+    ///
+    /// Empty if no extension has been negotiated.
+    pub fn extensions(&self) -> &str {
+        &self.extensions
+    }
+
+    /// Whether the extension with the specified name, for example `permessage-deflate`,
+    /// has been negotiated.
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions
+            .split(',')
+            .filter_map(|ext| ext.split(';').next())
+            .any(|ext| ext.trim().eq_ignore_ascii_case(name))
+    }
+
+    /// The used WebSocket browser interface.
+    pub fn interface(&self) -> Interface {
+        self.interface
+    }
+
+    /// The time when connecting was started.
+    pub fn connect_started_at(&self) -> SystemTime {
+        system_time(self.connect_started)
+    }
+
+    /// The time when the connection was established.
+    pub fn opened_at(&self) -> SystemTime {
+        system_time(self.opened)
+    }
+
+    /// The time it took to establish the connection, including the opening handshake.
+    pub fn handshake_duration(&self) -> Duration {
+        Duration::from_secs_f64((self.opened - self.connect_started).max(0.) / 1000.)
+    }
+}
+
+/// Converts a time obtained from [now](crate::util::now) into a system time.
+fn system_time(time: f64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs_f64(unix_time(time).max(0.) / 1000.)
+}
@@ -0,0 +1,136 @@
+//! Messages residing in JavaScript memory.
+
+use js_sys::{ArrayBuffer, JsString, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Blob;
+
+use crate::{Msg, WebSocketError};
+
+/// A WebSocket message residing in JavaScript memory.
+///
+/// Use it to pass received messages on to JavaScript APIs, for example `postMessage`,
+/// without copying them into Wasm memory and back. Likewise, sending a [JsMsg] sends
+/// data that already resides in JavaScript, for example a [Blob] from a file picker,
+/// without copying it into Wasm memory.
+///
+/// Received text messages are provided as [JsMsg::Text] and received binary messages
+/// as [JsMsg::ArrayBuffer], or as [JsMsg::Blob] if [receiving blobs](crate::WebSocketBuilder::set_receive_blobs).
+#[derive(Debug, Clone)]
+pub enum JsMsg {
+    /// Text message.
+    Text(JsString),
+    /// Binary message provided as a view into an `ArrayBuffer`.
+    Binary(Uint8Array),
+    /// Binary message provided as an `ArrayBuffer`.
+    ArrayBuffer(ArrayBuffer),
+    /// Binary message provided as a [Blob].
+    ///
+    /// Use [BlobReader](crate::BlobReader) to read it incrementally.
+    Blob(Blob),
+}
+
+impl JsMsg {
+    /// Whether this is a text message.
+    pub const fn is_text(&self) -> bool {
+        matches!(self, Self::Text(_))
+    }
+
+    /// Whether this is a binary message.
+    pub const fn is_binary(&self) -> bool {
+        !self.is_text()
+    }
+
+    /// Copies the message into Wasm memory.
+    ///
+    /// # Panics
+    /// Panics for a [blob](Self::Blob), since it can only be read asynchronously.
+    /// Use [read_msg](Self::read_msg) instead.
+    pub fn to_msg(&self) -> Msg {
+        match self {
+            Self::Text(text) => Msg::Text(text.into()),
+            Self::Binary(array) => Msg::Binary(array.to_vec()),
+            Self::ArrayBuffer(buffer) => Msg::Binary(Uint8Array::new(buffer).to_vec()),
+            Self::Blob(_) => panic!("blob can only be read asynchronously"),
+        }
+    }
+
+    /// Size used for send buffer accounting.
+    ///
+    /// Since the size of a JavaScript string in bytes is only known after encoding it,
+    /// its length in UTF-16 code units is used.
+    pub(crate) fn send_len(&self) -> usize {
+        match self {
+            Self::Text(text) => text.length() as usize,
+            Self::Binary(array) => array.byte_length() as usize,
+            Self::ArrayBuffer(buffer) => buffer.byte_length() as usize,
+            Self::Blob(blob) => blob.size() as usize,
+        }
+    }
+
+    /// Copies the message into Wasm memory, reading a [blob](Self::Blob) if necessary.
+    pub async fn read_msg(&self) -> Result<Msg, WebSocketError> {
+        match self {
+            Self::Blob(blob) => {
+                let buffer = JsFuture::from(blob.array_buffer()).await.map_err(|err| WebSocketError::js(&err))?;
+                Ok(Msg::Binary(Uint8Array::new(&buffer).to_vec()))
+            }
+            other => Ok(other.to_msg()),
+        }
+    }
+}
+
+impl From<&Msg> for JsMsg {
+    fn from(msg: &Msg) -> Self {
+        match msg {
+            Msg::Text(text) => Self::Text(text.as_str().into()),
+            Msg::Binary(vec) => Self::ArrayBuffer(Uint8Array::from(vec.as_slice()).buffer()),
+        }
+    }
+}
+
+impl From<JsString> for JsMsg {
+    fn from(text: JsString) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<Uint8Array> for JsMsg {
+    fn from(array: Uint8Array) -> Self {
+        Self::Binary(array)
+    }
+}
+
+impl From<ArrayBuffer> for JsMsg {
+    fn from(buffer: ArrayBuffer) -> Self {
+        Self::ArrayBuffer(buffer)
+    }
+}
+
+impl From<Blob> for JsMsg {
+    fn from(blob: Blob) -> Self {
+        Self::Blob(blob)
+    }
+}
+
+impl AsRef<JsValue> for JsMsg {
+    fn as_ref(&self) -> &JsValue {
+        match self {
+            Self::Text(text) => text,
+            Self::Binary(array) => array,
+            Self::ArrayBuffer(buffer) => buffer,
+            Self::Blob(blob) => blob,
+        }
+    }
+}
+
+impl From<JsMsg> for JsValue {
+    fn from(msg: JsMsg) -> Self {
+        match msg {
+            JsMsg::Text(text) => text.into(),
+            JsMsg::Binary(array) => array.into(),
+            JsMsg::ArrayBuffer(buffer) => buffer.into(),
+            JsMsg::Blob(blob) => blob.into(),
+        }
+    }
+}
@@ -18,6 +18,9 @@
 //! [WebSocket] and [WebSocketSender] implement the [Sink] trait for sending messages.
 //! A Rust [String] or [`&str`](str) is sent as a text message and
 //! a [`Vec<u8>`] or `&[u8]` is transmitted as a binary message.
+//! With the `bytes` feature enabled, `Bytes` and `BytesMut` are also sent as binary messages.
+//! Data that already resides in JavaScript, such as a `Blob`, can be sent as a [JsMsg]
+//! without copying it into Wasm memory.
 //!
 //! Additionally, both types implement [AsyncWrite]. When using this trait, each write
 //! is sent as a binary message containg the whole buffer.
@@ -31,6 +34,21 @@
 //! message is converted to binary format and buffered to support partial reads, i.e.
 //! a read using a buffer with a size smaller than the received message.
 //!
+//! To avoid copies, messages can also be received into a provided buffer using
+//! [recv_into](WebSocket::recv_into), kept in JavaScript memory as [JsMsg] using
+//! [recv_js](WebSocket::recv_js) or, with the `bytes` feature enabled, received as
+//! `Bytes` using `recv_bytes`.
+//!
+//! ## Reconnecting
+//!
+//! [ReconnectingWebSocket] wraps a [WebSocketBuilder] and transparently reestablishes
+//! the connection using exponential [Backoff] with jitter when it is closed with a
+//! [transient close code](CloseCode::is_transient).
+//! [Connection events](ReconnectEvent) can be observed, for example to inform the user.
+//!
+//! Since browsers do not expose WebSocket ping and pong frames, a [Heartbeat] can
+//! be configured to detect dead peers on half-open connections.
+//!
 //! ## Example
 //!
 //! The following example establishes a WebSocket connection to `localhost` on port `8765`.
@@ -61,27 +79,61 @@
 #[cfg(not(target_family = "wasm"))]
 compile_error!("websocket-web requires a WebAssembly target");
 
+mod abort;
+mod blob;
 mod closed;
+mod error;
+mod flow;
+mod heartbeat;
+mod info;
+mod js_msg;
+mod limit;
+mod read;
+mod reconnect;
+mod shared;
 mod standard;
+mod state;
 mod stream;
 mod util;
 
 use futures_core::Stream;
 use futures_sink::Sink;
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{
+    future::{select, Either},
+    SinkExt, StreamExt,
+};
 use js_sys::{Reflect, Uint8Array};
+use read::{ReadState, Received};
 use std::{
-    fmt, io,
-    io::ErrorKind,
-    pin::Pin,
+    fmt,
+    future::{poll_fn, Future, IntoFuture},
+    io,
+    pin::{pin, Pin},
     rc::Rc,
     task::{ready, Context, Poll},
+    time::Duration,
+};
+use stream::Payload;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::watch,
 };
-use tokio::io::{AsyncRead, AsyncWrite};
-use util::uint8_array_for_api;
+use util::{aborted, poll_recv_many, timeout, uint8_array_for_api};
 use wasm_bindgen::prelude::*;
-
-pub use closed::{CloseCode, Closed, ClosedReason};
+use web_sys::AbortSignal;
+
+pub use abort::AbortHandle;
+pub use blob::BlobReader;
+pub use closed::{CloseCode, CloseHandle, CloseInitiator, Closed, ClosedReason};
+pub use error::WebSocketError;
+pub use flow::FlowControl;
+pub use heartbeat::Heartbeat;
+pub use info::ConnectionInfo;
+pub use js_msg::JsMsg;
+pub use limit::ReceiveOverflowPolicy;
+pub use reconnect::{Backoff, ReconnectEvent, ReconnectEvents, ReconnectingWebSocket};
+pub use shared::SharedSender;
+pub use state::{ConnectionState, StateChanges};
 
 /// The WebSocket API used to interact with the JavaScript runtime.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -107,6 +159,25 @@ impl Interface {
     }
 }
 
+/// Determines where received messages are held until the application receives them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReceiveMode {
+    /// Text messages are converted into Rust strings once they arrive.
+    /// Binary messages stay in JavaScript memory until received.
+    #[default]
+    Msg,
+    /// All messages stay in JavaScript memory until received.
+    ///
+    /// Messages can be received as [JsMsg] using [recv_js](WebSocket::recv_js) without ever
+    /// being copied into Wasm memory. Messages received as [Msg] are converted on demand.
+    ///
+    /// Since the size of a text message in bytes is only known after conversion,
+    /// it is measured in UTF-16 code units for receive limits and buffers.
+    /// If a [Heartbeat] or [coalescing](ReceiveOverflowPolicy::Coalesce) is used,
+    /// messages are still converted when they arrive.
+    Js,
+}
+
 /// A WebSocket message.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Msg {
@@ -167,6 +238,27 @@ impl From<Msg> for Vec<u8> {
     }
 }
 
+#[cfg(feature = "bytes")]
+impl From<Msg> for bytes::Bytes {
+    fn from(msg: Msg) -> Self {
+        msg.to_vec().into()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl From<bytes::Bytes> for Msg {
+    fn from(bytes: bytes::Bytes) -> Self {
+        Self::Binary(bytes.into())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl From<bytes::BytesMut> for Msg {
+    fn from(bytes: bytes::BytesMut) -> Self {
+        Self::Binary(bytes.into())
+    }
+}
+
 impl AsRef<[u8]> for Msg {
     fn as_ref(&self) -> &[u8] {
         match self {
@@ -184,6 +276,20 @@ pub struct WebSocketBuilder {
     interface: Option<Interface>,
     send_buffer_size: Option<usize>,
     receive_buffer_size: Option<usize>,
+    receive_buffer_messages: Option<usize>,
+    receive_overflow_policy: ReceiveOverflowPolicy,
+    max_message_size: Option<usize>,
+    max_message_rate: Option<(u32, u32)>,
+    connect_timeout: Option<Duration>,
+    send_timeout: Option<Duration>,
+    receive_timeout: Option<Duration>,
+    close_timeout: Option<Duration>,
+    abort_signal: Option<AbortSignal>,
+    heartbeat: Option<Heartbeat>,
+    flow_control: Option<FlowControl>,
+    read_prefetch: bool,
+    receive_mode: ReceiveMode,
+    receive_blobs: bool,
 }
 
 impl WebSocketBuilder {
@@ -195,6 +301,20 @@ impl WebSocketBuilder {
             interface: None,
             send_buffer_size: None,
             receive_buffer_size: None,
+            receive_buffer_messages: None,
+            receive_overflow_policy: ReceiveOverflowPolicy::Close,
+            max_message_size: None,
+            max_message_rate: None,
+            connect_timeout: None,
+            send_timeout: None,
+            receive_timeout: None,
+            close_timeout: None,
+            abort_signal: None,
+            heartbeat: None,
+            flow_control: None,
+            read_prefetch: true,
+            receive_mode: ReceiveMode::Msg,
+            receive_blobs: false,
         }
     }
 
@@ -242,8 +362,8 @@ impl WebSocketBuilder {
     ///
     /// This only affects the [standard WebSocket interface](Interface::Standard).
     ///
-    /// If the maximum receive buffer size is reached, the WebSocket is closed and an
-    /// error is returned when trying to read from it.
+    /// If the maximum receive buffer size is reached, the [receive overflow policy](Self::set_receive_overflow_policy)
+    /// is applied. By default, the WebSocket is closed and an error is returned when trying to read from it.
     ///
     /// When using the [stream-baed WebSocket interface](Interface::Stream), the receive
     /// buffer size is fully managed by the browser.
@@ -251,8 +371,188 @@ impl WebSocketBuilder {
         self.receive_buffer_size = Some(receive_buffer_size);
     }
 
+    /// Sets the maximum number of messages in the receive buffer.
+    ///
+    /// This only affects the [standard WebSocket interface](Interface::Standard).
+    ///
+    /// If the maximum number of messages is reached, the
+    /// [receive overflow policy](Self::set_receive_overflow_policy) is applied.
+    ///
+    /// By default, the number of messages is only limited by the receive buffer size.
+    pub fn set_receive_buffer_messages(&mut self, receive_buffer_messages: usize) {
+        self.receive_buffer_messages = Some(receive_buffer_messages);
+    }
+
+    /// Sets the policy applied when a received message does not fit into the receive buffer.
+    ///
+    /// This only affects the [standard WebSocket interface](Interface::Standard).
+    ///
+    /// By default, the WebSocket is closed.
+    pub fn set_receive_overflow_policy(&mut self, receive_overflow_policy: ReceiveOverflowPolicy) {
+        self.receive_overflow_policy = receive_overflow_policy;
+    }
+
+    /// Sets whether the next message is read from the browser before
+    /// the application asks for it.
+    ///
+    /// This only affects the [stream-based WebSocket interface](Interface::Stream).
+    ///
+    /// Prefetching reduces the latency of receiving, but pulls one message more out of the
+    /// browser than the application consumed. When disabled, an application that stops
+    /// receiving, for example by [pausing](WebSocketReceiver::pause), exerts backpressure
+    /// on the server without any message being pulled in advance.
+    ///
+    /// By default, prefetching is enabled.
+    pub fn set_read_prefetch(&mut self, read_prefetch: bool) {
+        self.read_prefetch = read_prefetch;
+    }
+
+    /// Sets where received messages are held until the application receives them.
+    ///
+    /// By default, [ReceiveMode::Msg] is used.
+    pub fn set_receive_mode(&mut self, receive_mode: ReceiveMode) {
+        self.receive_mode = receive_mode;
+    }
+
+    /// Sets whether binary messages are received as blobs.
+    ///
+    /// This only affects the [standard WebSocket interface](Interface::Standard).
+    ///
+    /// A blob may be backed by disk storage of the browser instead of memory, thus
+    /// this is suitable for very large messages. Receive them using [recv_js](WebSocket::recv_js)
+    /// as [JsMsg::Blob] and read them incrementally using [BlobReader]. [AsyncRead] also
+    /// reads blobs incrementally. Receiving a blob as [Msg] reads it into Wasm memory as a whole.
+    ///
+    /// If a [Heartbeat] is used, pongs can only be detected in text messages.
+    ///
+    /// By default, binary messages are received as `ArrayBuffer`s.
+    pub fn set_receive_blobs(&mut self, receive_blobs: bool) {
+        self.receive_blobs = receive_blobs;
+    }
+
+    /// Sets the maximum size in bytes of a received message.
+    ///
+    /// If a larger message is received, the WebSocket is closed and receiving fails
+    /// with [WebSocketError::Closed] reporting [CloseCode::MessageTooBig].
+    /// This close code is only reported locally, see [CloseCode].
+    ///
+    /// By default, the message size is not limited.
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = Some(max_message_size);
+    }
+
+    /// Sets the maximum number of received messages per second and
+    /// the maximum number of messages that may be received in a burst.
+    ///
+    /// If messages arrive faster, the WebSocket is closed and receiving fails
+    /// with [WebSocketError::Closed] reporting [CloseCode::PolicyViolation].
+    /// This close code is only reported locally, see [CloseCode].
+    ///
+    /// By default, the message rate is not limited.
+    pub fn set_max_message_rate(&mut self, messages_per_second: u32, burst: u32) {
+        self.max_message_rate = Some((messages_per_second, burst));
+    }
+
+    /// Sets the maximum time for establishing the connection.
+    ///
+    /// If the connection is not established within the specified duration, the
+    /// WebSocket is closed and [WebSocketError::TimedOut] is returned.
+    ///
+    /// By default, there is no timeout.
+    pub fn set_connect_timeout(&mut self, connect_timeout: Duration) {
+        self.connect_timeout = Some(connect_timeout);
+    }
+
+    /// Sets the maximum time a send operation may wait for space in the send buffer.
+    ///
+    /// If a message cannot be handed to the browser within the specified duration,
+    /// the WebSocket is closed and [WebSocketError::TimedOut] is returned.
+    ///
+    /// By default, there is no timeout.
+    pub fn set_send_timeout(&mut self, send_timeout: Duration) {
+        self.send_timeout = Some(send_timeout);
+    }
+
+    /// Sets the maximum time without receiving a message.
+    ///
+    /// If no message is received within the specified duration while
+    /// waiting for one, the WebSocket is closed and
+    /// [WebSocketError::TimedOut] is returned.
+    ///
+    /// By default, there is no timeout.
+    pub fn set_receive_timeout(&mut self, receive_timeout: Duration) {
+        self.receive_timeout = Some(receive_timeout);
+    }
+
+    /// Sets the maximum time for completing the close handshake after
+    /// the WebSocket has been closed locally.
+    ///
+    /// If the server does not complete the close handshake within the specified duration,
+    /// [WebSocket::closed] resolves with [CloseCode::AbnormalClosure] and receiving fails
+    /// with [WebSocketError::TimedOut].
+    ///
+    /// By default, there is no timeout.
+    pub fn set_close_timeout(&mut self, close_timeout: Duration) {
+        self.close_timeout = Some(close_timeout);
+    }
+
+    /// Sets an abort signal for cancelling the connection.
+    ///
+    /// When the signal is aborted while connecting, [connect](Self::connect) fails
+    /// with [WebSocketError::Aborted]. When it is aborted after
+    /// the connection has been established, the WebSocket is closed and
+    /// all further I/O fails with [WebSocketError::Aborted].
+    ///
+    /// Use an [AbortHandle] to abort from Rust.
+    pub fn set_abort_signal(&mut self, abort_signal: AbortSignal) {
+        self.abort_signal = Some(abort_signal);
+    }
+
+    /// Enables the application-level heartbeat for detecting dead peers.
+    ///
+    /// See [Heartbeat] for details.
+    ///
+    /// By default, no heartbeat is sent.
+    pub fn set_heartbeat(&mut self, heartbeat: Heartbeat) {
+        self.heartbeat = Some(heartbeat);
+    }
+
+    /// Enables cooperative, credit-based flow control for received messages.
+    ///
+    /// The server must implement the protocol described in [FlowControl].
+    ///
+    /// By default, flow control is disabled.
+    pub fn set_flow_control(&mut self, flow_control: FlowControl) {
+        self.flow_control = Some(flow_control);
+    }
+
     /// Establishes the WebSocket connection.
-    pub async fn connect(self) -> io::Result<WebSocket> {
+    pub async fn connect(self) -> Result<WebSocket, WebSocketError> {
+        let Some(abort_signal) = self.abort_signal.clone() else { return self.connect_with_timeout().await };
+        if abort_signal.aborted() {
+            return Err(WebSocketError::Aborted);
+        }
+
+        let connect = pin!(self.connect_with_timeout());
+        let aborted = pin!(aborted(&abort_signal));
+        match select(connect, aborted).await {
+            Either::Left((_, _)) if abort_signal.aborted() => Err(WebSocketError::Aborted),
+            Either::Left((res, _)) => res,
+            Either::Right(((), _)) => Err(WebSocketError::Aborted),
+        }
+    }
+
+    async fn connect_with_timeout(self) -> Result<WebSocket, WebSocketError> {
+        match self.connect_timeout {
+            Some(connect_timeout) => match timeout(connect_timeout, self.connect_inner()).await {
+                Some(res) => res,
+                None => Err(WebSocketError::TimedOut),
+            },
+            None => self.connect_inner().await,
+        }
+    }
+
+    async fn connect_inner(self) -> Result<WebSocket, WebSocketError> {
         let interface = match self.interface {
             Some(interface) => interface,
             None if Interface::Stream.is_supported() => Interface::Stream,
@@ -260,42 +560,45 @@ impl WebSocketBuilder {
         };
 
         if !interface.is_supported() {
-            match interface {
-                Interface::Stream => {
-                    return Err(io::Error::new(ErrorKind::Unsupported, "WebSocketStream not supported"))
-                }
-                Interface::Standard => {
-                    return Err(io::Error::new(ErrorKind::Unsupported, "WebSocket not supported"))
-                }
-            }
+            return Err(WebSocketError::Unsupported(interface));
         }
 
         match interface {
             Interface::Stream => {
                 let (stream, info) = stream::Inner::new(self).await?;
-                Ok(WebSocket { inner: Inner::Stream(stream), info: Rc::new(info), read_buf: Vec::new() })
+                Ok(WebSocket {
+                    inner: Inner::Stream(stream),
+                    info: Rc::new(info),
+                    read_state: ReadState::default(),
+                })
             }
             Interface::Standard => {
                 let (standard, info) = standard::Inner::new(self).await?;
-                Ok(WebSocket { inner: Inner::Standard(standard), info: Rc::new(info), read_buf: Vec::new() })
+                Ok(WebSocket {
+                    inner: Inner::Standard(standard),
+                    info: Rc::new(info),
+                    read_state: ReadState::default(),
+                })
             }
         }
     }
 }
 
 struct Info {
-    url: String,
-    protocol: String,
-    interface: Interface,
+    connection: ConnectionInfo,
+    state_rx: watch::Receiver<ConnectionState>,
 }
 
 /// A WebSocket provided by the JavaScript runtime (usually the web browser).
 ///
+/// Receiving ends once the WebSocket has been closed cleanly. If it has been closed
+/// uncleanly, receiving fails with [WebSocketError::Closed] carrying the [ClosedReason].
+///
 /// The WebSocket is closed when dropped.
 pub struct WebSocket {
     inner: Inner,
     info: Rc<Info>,
-    read_buf: Vec<u8>,
+    read_state: ReadState,
 }
 
 enum Inner {
@@ -306,7 +609,7 @@ enum Inner {
 impl fmt::Debug for WebSocket {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("WebSocket")
-            .field("url", &self.info.url)
+            .field("url", &self.info.connection.url)
             .field("protocol", &self.protocol())
             .field("interface", &self.interface())
             .finish()
@@ -315,13 +618,13 @@ impl fmt::Debug for WebSocket {
 
 impl WebSocket {
     /// Connect to the specified WebSocket URL using default options.
-    pub async fn connect(url: impl AsRef<str>) -> io::Result<Self> {
+    pub async fn connect(url: impl AsRef<str>) -> Result<Self, WebSocketError> {
         WebSocketBuilder::new(url).connect().await
     }
 
     /// The URL of the WebSocket server.
     pub fn url(&self) -> &str {
-        &self.info.url
+        &self.info.connection.url
     }
 
     /// A string representing the sub-protocol used to open the current WebSocket connection
@@ -330,29 +633,43 @@ impl WebSocket {
     /// Returns an empty string if no sub-protocol has been used to open the connection
     /// (i.e. no sub-protocol options were specified in the [WebSocketBuilder]).
     pub fn protocol(&self) -> &str {
-        &self.info.protocol
+        &self.info.connection.protocol
     }
 
     /// The used WebSocket browser interface.
     pub fn interface(&self) -> Interface {
-        self.info.interface
+        self.info.connection.interface
+    }
+
+    /// Information about the established connection.
+    pub fn connection_info(&self) -> &ConnectionInfo {
+        &self.info.connection
+    }
+
+    /// The current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        *self.info.state_rx.borrow()
+    }
+
+    /// A stream of connection state changes, starting with the current state.
+    pub fn state_changes(&self) -> StateChanges {
+        StateChanges::new(self.info.state_rx.clone())
     }
 
     /// Splits this WebSocket into a sender and receiver.
     pub fn into_split(self) -> (WebSocketSender, WebSocketReceiver) {
-        let Self { inner, info, read_buf } = self;
+        let Self { inner, info, read_state } = self;
         match inner {
             Inner::Stream(inner) => {
                 let (sender, receiver) = inner.into_split();
                 let sender = WebSocketSender { inner: SenderInner::Stream(sender), info: info.clone() };
-                let receiver = WebSocketReceiver { inner: ReceiverInner::Stream(receiver), info, read_buf };
+                let receiver = WebSocketReceiver { inner: ReceiverInner::Stream(receiver), info, read_state };
                 (sender, receiver)
             }
             Inner::Standard(inner) => {
                 let (sender, receiver) = inner.into_split();
                 let sender = WebSocketSender { inner: SenderInner::Standard(sender), info: info.clone() };
-                let receiver =
-                    WebSocketReceiver { inner: ReceiverInner::Standard(receiver), info, read_buf: Vec::new() };
+                let receiver = WebSocketReceiver { inner: ReceiverInner::Standard(receiver), info, read_state };
                 (sender, receiver)
             }
         }
@@ -373,6 +690,35 @@ impl WebSocket {
         self.into_split().0.close_with_reason(code, reason);
     }
 
+    /// Gracefully closes the WebSocket with the specified close code and reason.
+    ///
+    /// The close frame is sent immediately. The returned [GracefulClose] yields
+    /// the messages that are still received and can be awaited to obtain
+    /// the final [ClosedReason], which includes the close code sent by the server.
+    ///
+    /// When using the [standard WebSocket interface](Interface::Standard), the browser
+    /// discards messages arriving after the close frame has been sent. Thus only
+    /// messages that have been received before are yielded.
+    ///
+    /// ## Panics
+    /// Panics if the close code is neither [CloseCode::NormalClosure] nor
+    /// [CloseCode::Other] with a value between 3000 and 4999.
+    #[track_caller]
+    pub fn close_gracefully(self, code: CloseCode, reason: &str) -> GracefulClose {
+        let closed = self.closed();
+        let (sender, receiver) = self.into_split();
+        sender.close_with_reason(code, reason);
+        GracefulClose { receiver, closed }
+    }
+
+    /// Returns a cloneable handle for closing the WebSocket.
+    pub fn close_handle(&self) -> CloseHandle {
+        match &self.inner {
+            Inner::Stream(inner) => CloseHandle::stream(inner.sender.guard()),
+            Inner::Standard(inner) => CloseHandle::standard(inner.sender.guard()),
+        }
+    }
+
     /// Returns a future that resolves when the WebSocket is closed remotely.
     pub fn closed(&self) -> Closed {
         match &self.inner {
@@ -381,23 +727,167 @@ impl WebSocket {
         }
     }
 
-    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+    /// Number of received messages dropped by the [receive overflow policy](ReceiveOverflowPolicy).
+    ///
+    /// An application can compare this value before and after receiving to detect
+    /// that messages were lost and it needs to resynchronize.
+    pub fn dropped_messages(&self) -> u64 {
+        match &self.inner {
+            Inner::Stream(_) => 0,
+            Inner::Standard(inner) => inner.receiver.dropped(),
+        }
+    }
+
+    /// Pauses receiving.
+    ///
+    /// See [WebSocketReceiver::pause] for details.
+    pub fn pause(&mut self) {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.pause(),
+            Inner::Standard(inner) => inner.receiver.pause(),
+        }
+    }
+
+    /// Resumes receiving after it has been [paused](Self::pause).
+    pub fn resume(&mut self) {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.resume(),
+            Inner::Standard(inner) => inner.receiver.resume(),
+        }
+    }
+
+    /// Whether receiving is paused.
+    pub fn is_paused(&self) -> bool {
+        match &self.inner {
+            Inner::Stream(inner) => inner.receiver.is_paused(),
+            Inner::Standard(inner) => inner.receiver.is_paused(),
+        }
+    }
+
+    /// Receives multiple messages at once.
+    ///
+    /// See [WebSocketReceiver::recv_many] for details.
+    pub async fn recv_many(&mut self, buf: &mut Vec<Msg>, limit: usize) -> Result<usize, WebSocketError> {
+        poll_fn(|cx| poll_recv_many(self, cx, buf, limit)).await
+    }
+
+    /// Receives a message if one is available without waiting.
+    ///
+    /// See [WebSocketReceiver::try_recv] for details.
+    pub fn try_recv(&mut self) -> Result<Option<Msg>, WebSocketError> {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.try_recv(),
+            Inner::Standard(inner) => inner.receiver.try_recv(),
+        }
+    }
+
+    /// Returns the next message if one is available without waiting, but does not remove it.
+    ///
+    /// See [WebSocketReceiver::peek] for details.
+    pub fn peek(&mut self) -> Result<Option<&Msg>, WebSocketError> {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.peek(),
+            Inner::Standard(inner) => inner.receiver.peek(),
+        }
+    }
+
+    /// Number of messages that are available without waiting.
+    ///
+    /// See [WebSocketReceiver::queued_len] for details.
+    pub fn queued_len(&mut self) -> usize {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.queued_len(),
+            Inner::Standard(inner) => inner.receiver.queued_len(),
+        }
+    }
+
+    /// Size in bytes of the messages that are available without waiting.
+    ///
+    /// See [WebSocketReceiver::queued_bytes] for details.
+    pub fn queued_bytes(&mut self) -> usize {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.queued_bytes(),
+            Inner::Standard(inner) => inner.receiver.queued_bytes(),
+        }
+    }
+
+    /// Receives a message into the provided buffer.
+    ///
+    /// See [WebSocketReceiver::recv_into] for details.
+    pub async fn recv_into(&mut self, buf: &mut [u8]) -> Result<Option<usize>, WebSocketError> {
+        let Some(mut msg) = poll_fn(|cx| self.poll_recv_loaded(cx)).await.transpose()? else { return Ok(None) };
+        match msg.copy_into_slice(buf) {
+            Ok(len) => Ok(Some(len)),
+            Err(err) => {
+                self.unrecv(msg);
+                Err(err)
+            }
+        }
+    }
+
+    /// Receives a message into the provided vector, reusing its allocation.
+    ///
+    /// See [WebSocketReceiver::recv_into_vec] for details.
+    pub async fn recv_into_vec(&mut self, buf: &mut Vec<u8>) -> Result<Option<usize>, WebSocketError> {
+        let Some(mut msg) = poll_fn(|cx| self.poll_recv_loaded(cx)).await.transpose()? else { return Ok(None) };
+        Ok(Some(msg.copy_into_vec(buf)))
+    }
+
+    /// Receives a message as [Bytes](bytes::Bytes).
+    ///
+    /// See [WebSocketReceiver::recv_bytes] for details.
+    #[cfg(feature = "bytes")]
+    pub async fn recv_bytes(&mut self) -> Result<Option<bytes::Bytes>, WebSocketError> {
+        let Some(msg) = poll_fn(|cx| self.poll_recv_loaded(cx)).await.transpose()? else { return Ok(None) };
+        Ok(Some(msg.into_msg().into()))
+    }
+
+    /// Receives a message without copying it into Wasm memory.
+    ///
+    /// See [WebSocketReceiver::recv_js] for details.
+    pub async fn recv_js(&mut self) -> Result<Option<JsMsg>, WebSocketError> {
+        poll_fn(|cx| self.poll_recv(cx)).await.map(|res| res.map(Received::into_js)).transpose()
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context) -> Poll<Option<Result<Received, WebSocketError>>> {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.poll_recv(cx),
+            Inner::Standard(inner) => inner.receiver.poll_recv(cx),
+        }
+    }
+
+    fn poll_recv_loaded(&mut self, cx: &mut Context) -> Poll<Option<Result<Received, WebSocketError>>> {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.poll_recv_loaded(cx),
+            Inner::Standard(inner) => inner.receiver.poll_recv_loaded(cx),
+        }
+    }
+
+    fn unrecv(&mut self, msg: Received) {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.receiver.unrecv(msg),
+            Inner::Standard(inner) => inner.receiver.unrecv(msg),
+        }
+    }
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), WebSocketError>> {
         match &mut self.inner {
             Inner::Stream(inner) => inner.sender.poll_ready_unpin(cx),
             Inner::Standard(inner) => inner.sender.poll_ready_unpin(cx),
         }
     }
 
-    fn start_send(mut self: Pin<&mut Self>, item: &JsValue, len: usize) -> Result<(), io::Error> {
+    fn start_send(mut self: Pin<&mut Self>, item: &JsValue, len: usize) -> Result<(), WebSocketError> {
         match &mut self.inner {
             Inner::Stream(inner) => inner.sender.start_send_unpin((item, len)),
             Inner::Standard(inner) => inner.sender.start_send_unpin(item),
         }
     }
 
-    fn start_send_binary(mut self: Pin<&mut Self>, data: &[u8]) -> Result<(), io::Error> {
+    fn start_send_binary(mut self: Pin<&mut Self>, data: &[u8]) -> Result<(), WebSocketError> {
         match &mut self.inner {
             Inner::Stream(inner) => {
+                // Borrowed data cannot be held back until it can be lent, thus it is copied.
                 let array: JsValue = Uint8Array::from(data).into();
                 inner.sender.start_send_unpin((&array, data.len()))
             }
@@ -409,14 +899,21 @@ impl WebSocket {
         }
     }
 
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+    fn start_send_payload(mut self: Pin<&mut Self>, data: Payload) -> Result<(), WebSocketError> {
+        match &mut self.inner {
+            Inner::Stream(inner) => inner.sender.start_send_payload(data),
+            Inner::Standard(_) => self.start_send_binary(&data),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), WebSocketError>> {
         match &mut self.inner {
             Inner::Stream(inner) => inner.sender.poll_flush_unpin(cx),
             Inner::Standard(inner) => inner.sender.poll_flush_unpin(cx),
         }
     }
 
-    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), WebSocketError>> {
         match &mut self.inner {
             Inner::Stream(inner) => inner.sender.poll_close_unpin(cx),
             Inner::Standard(inner) => inner.sender.poll_close_unpin(cx),
@@ -425,7 +922,7 @@ impl WebSocket {
 }
 
 impl Sink<&str> for WebSocket {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -445,7 +942,7 @@ impl Sink<&str> for WebSocket {
 }
 
 impl Sink<String> for WebSocket {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -465,7 +962,7 @@ impl Sink<String> for WebSocket {
 }
 
 impl Sink<&[u8]> for WebSocket {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -485,14 +982,76 @@ impl Sink<&[u8]> for WebSocket {
 }
 
 impl Sink<Vec<u8>> for WebSocket {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
     }
 
     fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
-        self.start_send_binary(&item)
+        self.start_send_payload(item.into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_close(cx)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Sink<bytes::Bytes> for WebSocket {
+    type Error = WebSocketError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: bytes::Bytes) -> Result<(), Self::Error> {
+        self.start_send_payload(item.into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_close(cx)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Sink<bytes::BytesMut> for WebSocket {
+    type Error = WebSocketError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: bytes::BytesMut) -> Result<(), Self::Error> {
+        self.start_send_payload(item.freeze().into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_close(cx)
+    }
+}
+
+impl Sink<JsMsg> for WebSocket {
+    type Error = WebSocketError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: JsMsg) -> Result<(), Self::Error> {
+        self.start_send(item.as_ref(), item.send_len())
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
@@ -505,7 +1064,7 @@ impl Sink<Vec<u8>> for WebSocket {
 }
 
 impl Sink<Msg> for WebSocket {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -514,7 +1073,7 @@ impl Sink<Msg> for WebSocket {
     fn start_send(self: Pin<&mut Self>, item: Msg) -> Result<(), Self::Error> {
         match item {
             Msg::Text(text) => self.start_send(&JsValue::from_str(&text), text.len()),
-            Msg::Binary(vec) => self.start_send_binary(&vec),
+            Msg::Binary(vec) => self.start_send_payload(vec.into()),
         }
     }
 
@@ -535,16 +1094,16 @@ impl AsyncWrite for WebSocket {
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
-        self.poll_flush(cx)
+        self.poll_flush(cx).map_err(Into::into)
     }
 
     fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
-        self.poll_close(cx)
+        self.poll_close(cx).map_err(Into::into)
     }
 }
 
 impl Stream for WebSocket {
-    type Item = io::Result<Msg>;
+    type Item = Result<Msg, WebSocketError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         match &mut self.inner {
@@ -558,16 +1117,12 @@ impl AsyncRead for WebSocket {
     fn poll_read(
         mut self: Pin<&mut Self>, cx: &mut Context, buf: &mut tokio::io::ReadBuf,
     ) -> Poll<io::Result<()>> {
-        while self.read_buf.is_empty() {
-            let Some(msg) = ready!(self.as_mut().poll_next(cx)?) else { return Poll::Ready(Ok(())) };
-            self.read_buf = msg.to_vec();
+        while self.read_state.is_empty() {
+            let Some(msg) = ready!(self.poll_recv(cx)?) else { return Poll::Ready(Ok(())) };
+            self.read_state.set(msg);
         }
 
-        let to_copy = buf.remaining().min(self.read_buf.len());
-        buf.put_slice(&self.read_buf[..to_copy]);
-        self.read_buf.drain(..to_copy);
-
-        Poll::Ready(Ok(()))
+        self.read_state.poll_read(cx, buf)
     }
 }
 
@@ -588,7 +1143,7 @@ enum SenderInner {
 impl fmt::Debug for WebSocketSender {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("WebSocketSender")
-            .field("url", &self.info.url)
+            .field("url", &self.info.connection.url)
             .field("protocol", &self.protocol())
             .field("interface", &self.interface())
             .finish()
@@ -598,17 +1153,32 @@ impl fmt::Debug for WebSocketSender {
 impl WebSocketSender {
     /// The URL of the WebSocket server.
     pub fn url(&self) -> &str {
-        &self.info.url
+        &self.info.connection.url
     }
 
     /// A string representing the sub-protocol used to open the current WebSocket connection.
     pub fn protocol(&self) -> &str {
-        &self.info.protocol
+        &self.info.connection.protocol
     }
 
     /// The used WebSocket browser interface.
     pub fn interface(&self) -> Interface {
-        self.info.interface
+        self.info.connection.interface
+    }
+
+    /// Information about the established connection.
+    pub fn connection_info(&self) -> &ConnectionInfo {
+        &self.info.connection
+    }
+
+    /// The current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        *self.info.state_rx.borrow()
+    }
+
+    /// A stream of connection state changes, starting with the current state.
+    pub fn state_changes(&self) -> StateChanges {
+        StateChanges::new(self.info.state_rx.clone())
     }
 
     /// Closes the WebSocket.
@@ -637,23 +1207,63 @@ impl WebSocketSender {
         }
     }
 
-    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+    /// Returns a cloneable handle for closing the WebSocket.
+    pub fn close_handle(&self) -> CloseHandle {
+        match &self.inner {
+            SenderInner::Stream(inner) => CloseHandle::stream(inner.guard()),
+            SenderInner::Standard(inner) => CloseHandle::standard(inner.guard()),
+        }
+    }
+
+    /// Converts this sender into a cloneable sender for sending from multiple producers.
+    pub fn into_shared(self) -> SharedSender {
+        SharedSender::new(self)
+    }
+
+    /// Reunites this sender with the receiver obtained from the same [WebSocket::into_split]
+    /// call, restoring the original WebSocket.
+    ///
+    /// Data that has been received but not yet read via [AsyncRead] is preserved.
+    ///
+    /// Fails if the sender and receiver do not belong to the same WebSocket.
+    #[allow(clippy::result_large_err)]
+    pub fn reunite(self, receiver: WebSocketReceiver) -> Result<WebSocket, ReuniteError> {
+        if !Rc::ptr_eq(&self.info, &receiver.info) {
+            return Err(ReuniteError(self, receiver));
+        }
+
+        let WebSocketReceiver { inner: receiver_inner, info, read_state } = receiver;
+        let inner = match (self.inner, receiver_inner) {
+            (SenderInner::Stream(sender), ReceiverInner::Stream(receiver)) => {
+                Inner::Stream(stream::Inner::from_split(sender, receiver))
+            }
+            (SenderInner::Standard(sender), ReceiverInner::Standard(receiver)) => {
+                Inner::Standard(standard::Inner::from_split(sender, receiver))
+            }
+            _ => unreachable!("WebSocket halves use different interfaces"),
+        };
+
+        Ok(WebSocket { inner, info, read_state })
+    }
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), WebSocketError>> {
         match &mut self.inner {
             SenderInner::Stream(inner) => inner.poll_ready_unpin(cx),
             SenderInner::Standard(inner) => inner.poll_ready_unpin(cx),
         }
     }
 
-    fn start_send(mut self: Pin<&mut Self>, item: &JsValue, len: usize) -> Result<(), io::Error> {
+    fn start_send(mut self: Pin<&mut Self>, item: &JsValue, len: usize) -> Result<(), WebSocketError> {
         match &mut self.inner {
             SenderInner::Stream(inner) => inner.start_send_unpin((item, len)),
             SenderInner::Standard(inner) => inner.start_send_unpin(item),
         }
     }
 
-    fn start_send_binary(mut self: Pin<&mut Self>, data: &[u8]) -> Result<(), io::Error> {
+    fn start_send_binary(mut self: Pin<&mut Self>, data: &[u8]) -> Result<(), WebSocketError> {
         match &mut self.inner {
             SenderInner::Stream(inner) => {
+                // Borrowed data cannot be held back until it can be lent, thus it is copied.
                 let array: JsValue = Uint8Array::from(data).into();
                 inner.start_send_unpin((&array, data.len()))
             }
@@ -665,14 +1275,21 @@ impl WebSocketSender {
         }
     }
 
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+    fn start_send_payload(mut self: Pin<&mut Self>, data: Payload) -> Result<(), WebSocketError> {
+        match &mut self.inner {
+            SenderInner::Stream(inner) => inner.start_send_payload(data),
+            SenderInner::Standard(_) => self.start_send_binary(&data),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), WebSocketError>> {
         match &mut self.inner {
             SenderInner::Stream(inner) => inner.poll_flush_unpin(cx),
             SenderInner::Standard(inner) => inner.poll_flush_unpin(cx),
         }
     }
 
-    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), WebSocketError>> {
         match &mut self.inner {
             SenderInner::Stream(inner) => inner.poll_close_unpin(cx),
             SenderInner::Standard(inner) => inner.poll_close_unpin(cx),
@@ -681,7 +1298,7 @@ impl WebSocketSender {
 }
 
 impl Sink<&str> for WebSocketSender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -701,7 +1318,7 @@ impl Sink<&str> for WebSocketSender {
 }
 
 impl Sink<String> for WebSocketSender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -721,7 +1338,7 @@ impl Sink<String> for WebSocketSender {
 }
 
 impl Sink<&[u8]> for WebSocketSender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -741,14 +1358,76 @@ impl Sink<&[u8]> for WebSocketSender {
 }
 
 impl Sink<Vec<u8>> for WebSocketSender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
     }
 
     fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
-        self.start_send_binary(&item)
+        self.start_send_payload(item.into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_close(cx)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Sink<bytes::Bytes> for WebSocketSender {
+    type Error = WebSocketError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: bytes::Bytes) -> Result<(), Self::Error> {
+        self.start_send_payload(item.into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_close(cx)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Sink<bytes::BytesMut> for WebSocketSender {
+    type Error = WebSocketError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: bytes::BytesMut) -> Result<(), Self::Error> {
+        self.start_send_payload(item.freeze().into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_close(cx)
+    }
+}
+
+impl Sink<JsMsg> for WebSocketSender {
+    type Error = WebSocketError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: JsMsg) -> Result<(), Self::Error> {
+        self.start_send(item.as_ref(), item.send_len())
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
@@ -761,7 +1440,7 @@ impl Sink<Vec<u8>> for WebSocketSender {
 }
 
 impl Sink<Msg> for WebSocketSender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.poll_ready(cx)
@@ -770,7 +1449,7 @@ impl Sink<Msg> for WebSocketSender {
     fn start_send(self: Pin<&mut Self>, item: Msg) -> Result<(), Self::Error> {
         match item {
             Msg::Text(text) => self.start_send(&JsValue::from_str(&text), text.len()),
-            Msg::Binary(vec) => self.start_send_binary(&vec),
+            Msg::Binary(vec) => self.start_send_payload(vec.into()),
         }
     }
 
@@ -791,11 +1470,11 @@ impl AsyncWrite for WebSocketSender {
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
-        self.poll_flush(cx)
+        self.poll_flush(cx).map_err(Into::into)
     }
 
     fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), io::Error>> {
-        self.poll_close(cx)
+        self.poll_close(cx).map_err(Into::into)
     }
 }
 
@@ -806,7 +1485,7 @@ impl AsyncWrite for WebSocketSender {
 pub struct WebSocketReceiver {
     inner: ReceiverInner,
     info: Rc<Info>,
-    read_buf: Vec<u8>,
+    read_state: ReadState,
 }
 
 enum ReceiverInner {
@@ -817,7 +1496,7 @@ enum ReceiverInner {
 impl fmt::Debug for WebSocketReceiver {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("WebSocketReceiver")
-            .field("url", &self.info.url)
+            .field("url", &self.info.connection.url)
             .field("protocol", &self.protocol())
             .field("interface", &self.interface())
             .finish()
@@ -827,22 +1506,296 @@ impl fmt::Debug for WebSocketReceiver {
 impl WebSocketReceiver {
     /// The URL of the WebSocket server.
     pub fn url(&self) -> &str {
-        &self.info.url
+        &self.info.connection.url
     }
 
     /// A string representing the sub-protocol used to open the current WebSocket connection.
     pub fn protocol(&self) -> &str {
-        &self.info.protocol
+        &self.info.connection.protocol
     }
 
     /// The used WebSocket browser interface.
     pub fn interface(&self) -> Interface {
-        self.info.interface
+        self.info.connection.interface
+    }
+
+    /// Information about the established connection.
+    pub fn connection_info(&self) -> &ConnectionInfo {
+        &self.info.connection
+    }
+
+    /// The current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        *self.info.state_rx.borrow()
+    }
+
+    /// A stream of connection state changes, starting with the current state.
+    pub fn state_changes(&self) -> StateChanges {
+        StateChanges::new(self.info.state_rx.clone())
+    }
+
+    /// Returns a cloneable handle for closing the WebSocket.
+    pub fn close_handle(&self) -> CloseHandle {
+        match &self.inner {
+            ReceiverInner::Stream(inner) => CloseHandle::stream(inner.guard()),
+            ReceiverInner::Standard(inner) => CloseHandle::standard(inner.guard()),
+        }
+    }
+
+    /// Number of received messages dropped by the [receive overflow policy](ReceiveOverflowPolicy).
+    pub fn dropped_messages(&self) -> u64 {
+        match &self.inner {
+            ReceiverInner::Stream(_) => 0,
+            ReceiverInner::Standard(inner) => inner.dropped(),
+        }
+    }
+
+    /// Pauses receiving.
+    ///
+    /// While paused, receiving waits until [resumed](Self::resume) and the receive timeout
+    /// does not elapse. The behavior depends on which [WebSocket interface](Interface) is used:
+    ///
+    ///   * For the [stream-based WebSocket interface](Interface::Stream):
+    ///     no more messages are read from the browser, which thus exerts backpressure
+    ///     on the server. A message that has already been [prefetched](WebSocketBuilder::set_read_prefetch)
+    ///     is returned after resuming.
+    ///
+    ///   * For the [standard WebSocket interface](Interface::Standard):
+    ///     received messages are still buffered and count towards the
+    ///     [receive buffer size](WebSocketBuilder::set_receive_buffer_size).
+    pub fn pause(&mut self) {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.pause(),
+            ReceiverInner::Standard(inner) => inner.pause(),
+        }
+    }
+
+    /// Resumes receiving after it has been [paused](Self::pause).
+    pub fn resume(&mut self) {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.resume(),
+            ReceiverInner::Standard(inner) => inner.resume(),
+        }
+    }
+
+    /// Whether receiving is paused.
+    pub fn is_paused(&self) -> bool {
+        match &self.inner {
+            ReceiverInner::Stream(inner) => inner.is_paused(),
+            ReceiverInner::Standard(inner) => inner.is_paused(),
+        }
+    }
+
+    /// Receives multiple messages at once.
+    ///
+    /// Waits until at least one message is available and then appends all messages
+    /// that are available without waiting, but at most `limit`, to `buf`.
+    /// This avoids waking the receiving task for each message.
+    ///
+    /// For the [standard WebSocket interface](Interface::Standard), all messages in
+    /// the receive buffer are available. For the [stream-based WebSocket interface](Interface::Stream),
+    /// messages from reads that have already completed are available.
+    ///
+    /// Returns the number of received messages, which is zero if
+    /// the WebSocket has been closed cleanly or `limit` is zero.
+    pub async fn recv_many(&mut self, buf: &mut Vec<Msg>, limit: usize) -> Result<usize, WebSocketError> {
+        poll_fn(|cx| poll_recv_many(self, cx, buf, limit)).await
+    }
+
+    /// Receives a message if one is available without waiting.
+    ///
+    /// This allows polling for messages synchronously, for example once per
+    /// animation frame, without registering a waker.
+    ///
+    /// Returns `None` if no message is available. Fails with [WebSocketError::Closed]
+    /// once the WebSocket has been closed and all messages have been received.
+    pub fn try_recv(&mut self) -> Result<Option<Msg>, WebSocketError> {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.try_recv(),
+            ReceiverInner::Standard(inner) => inner.try_recv(),
+        }
+    }
+
+    /// Returns the next message if one is available without waiting, but does not remove it.
+    ///
+    /// The message is returned by the next receive.
+    pub fn peek(&mut self) -> Result<Option<&Msg>, WebSocketError> {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.peek(),
+            ReceiverInner::Standard(inner) => inner.peek(),
+        }
+    }
+
+    /// Number of messages that are available without waiting.
+    ///
+    /// The behavior depends on which [WebSocket interface](Interface) is used:
+    ///
+    ///   * For the [standard WebSocket interface](Interface::Standard):
+    ///     this is the number of messages in the receive buffer.
+    ///
+    ///   * For the [stream-based WebSocket interface](Interface::Stream):
+    ///     the receive buffer is managed by the browser and thus this is at most one,
+    ///     namely a message from a completed [prefetched](WebSocketBuilder::set_read_prefetch) read.
+    ///     The message is taken from the browser, as if [peeked](Self::peek).
+    pub fn queued_len(&mut self) -> usize {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.queued_len(),
+            ReceiverInner::Standard(inner) => inner.queued_len(),
+        }
+    }
+
+    /// Size in bytes of the messages that are available without waiting.
+    ///
+    /// See [queued_len](Self::queued_len) for details.
+    pub fn queued_bytes(&mut self) -> usize {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.queued_bytes(),
+            ReceiverInner::Standard(inner) => inner.queued_bytes(),
+        }
+    }
+
+    /// Receives a message into the provided buffer.
+    ///
+    /// The message data is copied directly from JavaScript into `buf`, without
+    /// allocating an intermediate [Vec]. Text messages are copied as UTF-8.
+    ///
+    /// Returns the length of the message, or `None` if the WebSocket has been closed cleanly.
+    /// If the message does not fit into `buf`, this fails with [WebSocketError::BufferTooSmall]
+    /// and the message is returned by the next receive.
+    ///
+    /// This is cancel safe: if the returned future is dropped, for example while a
+    /// [received blob](WebSocketBuilder::set_receive_blobs) is being read, no message is lost.
+    pub async fn recv_into(&mut self, buf: &mut [u8]) -> Result<Option<usize>, WebSocketError> {
+        let Some(mut msg) = poll_fn(|cx| self.poll_recv_loaded(cx)).await.transpose()? else { return Ok(None) };
+        match msg.copy_into_slice(buf) {
+            Ok(len) => Ok(Some(len)),
+            Err(err) => {
+                self.unrecv(msg);
+                Err(err)
+            }
+        }
+    }
+
+    /// Receives a message into the provided vector, reusing its allocation.
+    ///
+    /// The contents of `buf` are replaced by the message data, which is copied directly
+    /// from JavaScript. Thus, when receiving repeatedly into the same vector, no allocation
+    /// is necessary once it has grown to the size of the largest message.
+    ///
+    /// Returns the length of the message, or `None` if the WebSocket has been closed cleanly.
+    /// Like [recv_into](Self::recv_into), this is cancel safe.
+    pub async fn recv_into_vec(&mut self, buf: &mut Vec<u8>) -> Result<Option<usize>, WebSocketError> {
+        let Some(mut msg) = poll_fn(|cx| self.poll_recv_loaded(cx)).await.transpose()? else { return Ok(None) };
+        Ok(Some(msg.copy_into_vec(buf)))
+    }
+
+    /// Receives a message as [Bytes](bytes::Bytes).
+    ///
+    /// The message data is copied from JavaScript directly into the allocation backing the
+    /// returned [Bytes](bytes::Bytes), which can be cloned cheaply, for example to send it
+    /// to many WebSockets. Text messages are provided as UTF-8.
+    ///
+    /// Returns `None` if the WebSocket has been closed cleanly.
+    /// Like [recv_into](Self::recv_into), this is cancel safe.
+    #[cfg(feature = "bytes")]
+    pub async fn recv_bytes(&mut self) -> Result<Option<bytes::Bytes>, WebSocketError> {
+        let Some(msg) = poll_fn(|cx| self.poll_recv_loaded(cx)).await.transpose()? else { return Ok(None) };
+        Ok(Some(msg.into_msg().into()))
+    }
+
+    /// Receives a message without copying it into Wasm memory.
+    ///
+    /// Binary messages are provided as [JsMsg::ArrayBuffer], or as [JsMsg::Blob] if
+    /// [receiving blobs](WebSocketBuilder::set_receive_blobs), and text messages
+    /// as [JsMsg::Text]. Text messages are only kept in JavaScript memory when
+    /// [ReceiveMode::Js] is used and are otherwise copied back from Wasm memory.
+    ///
+    /// Returns `None` if the WebSocket has been closed cleanly.
+    pub async fn recv_js(&mut self) -> Result<Option<JsMsg>, WebSocketError> {
+        poll_fn(|cx| self.poll_recv(cx)).await.map(|res| res.map(Received::into_js)).transpose()
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context) -> Poll<Option<Result<Received, WebSocketError>>> {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.poll_recv(cx),
+            ReceiverInner::Standard(inner) => inner.poll_recv(cx),
+        }
+    }
+
+    fn poll_recv_loaded(&mut self, cx: &mut Context) -> Poll<Option<Result<Received, WebSocketError>>> {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.poll_recv_loaded(cx),
+            ReceiverInner::Standard(inner) => inner.poll_recv_loaded(cx),
+        }
+    }
+
+    fn unrecv(&mut self, msg: Received) {
+        match &mut self.inner {
+            ReceiverInner::Stream(inner) => inner.unrecv(msg),
+            ReceiverInner::Standard(inner) => inner.unrecv(msg),
+        }
+    }
+}
+
+/// Error returned by [WebSocketSender::reunite] when the sender and receiver
+/// do not belong to the same [WebSocket].
+///
+/// It contains both halves, so that they are not lost.
+#[derive(Debug)]
+pub struct ReuniteError(pub WebSocketSender, pub WebSocketReceiver);
+
+impl fmt::Display for ReuniteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tried to reunite halves that are not from the same WebSocket")
+    }
+}
+
+impl std::error::Error for ReuniteError {}
+
+/// A gracefully closing [WebSocket].
+///
+/// Yields the remaining received messages as a [Stream].
+/// Await it to discard the remaining messages and obtain the final [ClosedReason].
+///
+/// Obtained by calling [WebSocket::close_gracefully].
+pub struct GracefulClose {
+    receiver: WebSocketReceiver,
+    closed: Closed,
+}
+
+impl fmt::Debug for GracefulClose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GracefulClose").field("receiver", &self.receiver).finish_non_exhaustive()
+    }
+}
+
+impl GracefulClose {
+    /// Receives all remaining messages and waits for the close handshake to complete.
+    pub async fn finish(mut self) -> ClosedReason {
+        while let Some(Ok(_)) = self.receiver.next().await {}
+        self.closed.await
+    }
+}
+
+impl Stream for GracefulClose {
+    type Item = Result<Msg, WebSocketError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl IntoFuture for GracefulClose {
+    type Output = ClosedReason;
+    type IntoFuture = Pin<Box<dyn Future<Output = ClosedReason>>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.finish())
     }
 }
 
 impl Stream for WebSocketReceiver {
-    type Item = io::Result<Msg>;
+    type Item = Result<Msg, WebSocketError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         match &mut self.inner {
@@ -856,15 +1809,11 @@ impl AsyncRead for WebSocketReceiver {
     fn poll_read(
         mut self: Pin<&mut Self>, cx: &mut Context, buf: &mut tokio::io::ReadBuf,
     ) -> Poll<io::Result<()>> {
-        while self.read_buf.is_empty() {
-            let Some(msg) = ready!(self.as_mut().poll_next(cx)?) else { return Poll::Ready(Ok(())) };
-            self.read_buf = msg.to_vec();
+        while self.read_state.is_empty() {
+            let Some(msg) = ready!(self.poll_recv(cx)?) else { return Poll::Ready(Ok(())) };
+            self.read_state.set(msg);
         }
 
-        let to_copy = buf.remaining().min(self.read_buf.len());
-        buf.put_slice(&self.read_buf[..to_copy]);
-        self.read_buf.drain(..to_copy);
-
-        Poll::Ready(Ok(()))
+        self.read_state.poll_read(cx, buf)
     }
 }
@@ -0,0 +1,119 @@
+//! Inbound message limits.
+
+use std::{cell::Cell, fmt, rc::Rc};
+
+use crate::{util::now, CloseCode, CloseInitiator, ClosedReason, Msg};
+
+/// Policy applied when a received message does not fit into the receive buffer.
+///
+/// This only affects the [standard WebSocket interface](crate::Interface::Standard),
+/// since the receive buffer of the [stream-based WebSocket interface](crate::Interface::Stream)
+/// is managed by the browser.
+///
+/// Messages dropped by the policy are counted, see
+/// [WebSocket::dropped_messages](crate::WebSocket::dropped_messages).
+#[derive(Clone, Default)]
+pub enum ReceiveOverflowPolicy {
+    /// Close the WebSocket and fail with [WebSocketError::ReceiveOverflow](crate::WebSocketError::ReceiveOverflow).
+    #[default]
+    Close,
+    /// Drop the received message.
+    DropNewest,
+    /// Drop the oldest buffered messages until the received message fits.
+    DropOldest,
+    /// Replace the buffered message that has the same key as the received message.
+    ///
+    /// The key of a message is determined by the provided function.
+    /// Messages without a key are never coalesced.
+    /// If the received message still does not fit, the oldest buffered messages are dropped.
+    Coalesce(KeyFn),
+}
+
+/// Function determining the key of a message for coalescing.
+type KeyFn = Rc<dyn Fn(&Msg) -> Option<String>>;
+
+impl ReceiveOverflowPolicy {
+    /// Coalesce messages by the key returned by the specified function.
+    pub fn coalesce(key: impl Fn(&Msg) -> Option<String> + 'static) -> Self {
+        Self::Coalesce(Rc::new(key))
+    }
+}
+
+impl fmt::Debug for ReceiveOverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Close => write!(f, "Close"),
+            Self::DropNewest => write!(f, "DropNewest"),
+            Self::DropOldest => write!(f, "DropOldest"),
+            Self::Coalesce(_) => f.debug_tuple("Coalesce").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Enforces the inbound message limits configured on the [WebSocketBuilder](crate::WebSocketBuilder).
+pub(crate) struct ReceiveLimiter {
+    max_message_size: Option<usize>,
+    rate: Option<RateLimit>,
+}
+
+/// Token bucket limiting the number of messages per second.
+struct RateLimit {
+    per_ms: f64,
+    burst: f64,
+    tokens: Cell<f64>,
+    last: Cell<f64>,
+}
+
+impl ReceiveLimiter {
+    /// Creates a new limiter.
+    ///
+    /// `rate` specifies the maximum number of messages per second and the maximum burst.
+    pub fn new(max_message_size: Option<usize>, rate: Option<(u32, u32)>) -> Self {
+        Self {
+            max_message_size,
+            rate: rate.map(|(per_sec, burst)| {
+                let burst = f64::from(burst.max(1));
+                RateLimit {
+                    per_ms: f64::from(per_sec) / 1000.,
+                    burst,
+                    tokens: Cell::new(burst),
+                    last: Cell::new(now()),
+                }
+            }),
+        }
+    }
+
+    /// Accounts for a received message of the specified size in bytes.
+    ///
+    /// Returns the reason for closing the WebSocket if a limit has been violated.
+    pub fn check(&self, len: usize) -> Result<(), ClosedReason> {
+        if let Some(max_message_size) = self.max_message_size {
+            if len > max_message_size {
+                return Err(violation(
+                    CloseCode::MessageTooBig,
+                    format!("message size {len} exceeds limit of {max_message_size} bytes"),
+                ));
+            }
+        }
+
+        if let Some(rate) = &self.rate {
+            let now = now();
+            let elapsed = (now - rate.last.replace(now)).max(0.);
+            let tokens = (rate.tokens.get() + elapsed * rate.per_ms).min(rate.burst);
+            if tokens < 1. {
+                rate.tokens.set(tokens);
+                return Err(violation(CloseCode::PolicyViolation, "message rate limit exceeded".to_string()));
+            }
+            rate.tokens.set(tokens - 1.);
+        }
+
+        Ok(())
+    }
+}
+
+/// Close reason for a violated limit.
+///
+/// The close code is only reported locally, see [CloseCode].
+fn violation(code: CloseCode, reason: String) -> ClosedReason {
+    ClosedReason { code, reason, was_clean: false, initiator: CloseInitiator::Local }
+}
@@ -0,0 +1,209 @@
+//! Receiving into buffers.
+
+use js_sys::{JsString, Uint8Array};
+use std::{
+    io,
+    mem::{self, MaybeUninit},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, ReadBuf};
+use wasm_bindgen::prelude::*;
+use web_sys::Blob;
+
+use crate::{BlobReader, JsMsg, Msg, ReceiveMode, WebSocketError};
+
+/// A received message, which may still reside in JavaScript memory.
+pub(crate) enum Received {
+    /// Message in Wasm memory.
+    Msg(Msg),
+    /// Text message in JavaScript memory.
+    Text(JsString),
+    /// Binary message in JavaScript memory.
+    Binary(Uint8Array),
+    /// Binary message received as a blob.
+    Blob(Blob),
+}
+
+impl Received {
+    /// Wraps the data of a received message, which is either a string, an `ArrayBuffer` or a blob.
+    pub fn from_js(data: &JsValue, mode: ReceiveMode) -> Self {
+        if let Some(blob) = data.dyn_ref::<Blob>() {
+            return Self::Blob(blob.clone());
+        }
+        if !data.is_string() {
+            return Self::Binary(Uint8Array::new(data));
+        }
+        match mode {
+            ReceiveMode::Msg => Self::Msg(Msg::Text(data.as_string().unwrap())),
+            ReceiveMode::Js => Self::Text(data.clone().unchecked_into()),
+        }
+    }
+
+    /// Length of message.
+    ///
+    /// This is measured in UTF-16 code units for text messages in JavaScript memory
+    /// and in bytes otherwise.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Msg(msg) => msg.len(),
+            Self::Text(text) => text.length() as usize,
+            Self::Binary(array) => array.byte_length() as usize,
+            Self::Blob(blob) => blob.size() as usize,
+        }
+    }
+
+    /// Converts into a message, copying it into Wasm memory.
+    ///
+    /// Blobs must be read into JavaScript memory before.
+    pub fn into_msg(self) -> Msg {
+        match self {
+            Self::Msg(msg) => msg,
+            Self::Text(text) => Msg::Text(text.into()),
+            Self::Binary(array) => Msg::Binary(array.to_vec()),
+            Self::Blob(_) => unreachable!("blob must be loaded before conversion"),
+        }
+    }
+
+    /// Converts into a message residing in JavaScript memory.
+    pub fn into_js(self) -> JsMsg {
+        match self {
+            Self::Msg(msg) => JsMsg::from(&msg),
+            Self::Text(text) => JsMsg::Text(text),
+            Self::Binary(array) => JsMsg::ArrayBuffer(array.buffer()),
+            Self::Blob(blob) => JsMsg::Blob(blob),
+        }
+    }
+
+    /// Converts into a message in place and returns a reference to it.
+    ///
+    /// Returns `None` for a blob, since it can only be read asynchronously.
+    pub fn as_msg(&mut self) -> Option<&Msg> {
+        if let Self::Text(_) | Self::Binary(_) = self {
+            let received = mem::replace(self, Self::Msg(Msg::Binary(Vec::new())));
+            *self = Self::Msg(received.into_msg());
+        }
+        match self {
+            Self::Msg(msg) => Some(msg),
+            _ => None,
+        }
+    }
+
+    /// Converts a text message into Wasm memory, so that its length is measured in bytes.
+    fn text_to_wasm(&mut self) {
+        if let Self::Text(_) = self {
+            self.as_msg();
+        }
+    }
+
+    /// Copies the bytes of the message starting at `pos` into the beginning of `buf`,
+    /// which must have exactly the length of the copied range.
+    fn copy_to(&self, pos: usize, buf: &mut [MaybeUninit<u8>]) {
+        let end = pos + buf.len();
+        match self {
+            Self::Msg(msg) => {
+                for (dst, src) in buf.iter_mut().zip(&msg.as_ref()[pos..end]) {
+                    dst.write(*src);
+                }
+            }
+            Self::Binary(array) => {
+                array.subarray(pos as u32, end as u32).copy_to_uninit(buf);
+            }
+            Self::Text(_) | Self::Blob(_) => unreachable!("message must be converted before copying"),
+        }
+    }
+
+    /// Copies the message into the beginning of `buf`.
+    ///
+    /// Fails with [WebSocketError::BufferTooSmall] if it does not fit.
+    pub fn copy_into_slice(&mut self, buf: &mut [u8]) -> Result<usize, WebSocketError> {
+        self.text_to_wasm();
+        let len = self.len();
+        let Some(dst) = buf.get_mut(..len) else { return Err(WebSocketError::BufferTooSmall { required: len }) };
+        match self {
+            Self::Msg(msg) => dst.copy_from_slice(msg.as_ref()),
+            Self::Binary(array) => array.copy_to(dst),
+            Self::Text(_) | Self::Blob(_) => unreachable!("message must be converted before copying"),
+        }
+        Ok(len)
+    }
+
+    /// Replaces the contents of `buf` by the message, reusing its allocation.
+    pub fn copy_into_vec(&mut self, buf: &mut Vec<u8>) -> usize {
+        self.text_to_wasm();
+        let len = self.len();
+        buf.clear();
+        buf.reserve(len);
+        self.copy_to(0, &mut buf.spare_capacity_mut()[..len]);
+        // SAFETY: the first len bytes have been initialized.
+        unsafe { buf.set_len(len) };
+        len
+    }
+}
+
+impl From<Msg> for Received {
+    fn from(msg: Msg) -> Self {
+        Self::Msg(msg)
+    }
+}
+
+/// A partially read message for implementing [AsyncRead].
+///
+/// Data is copied directly from the received message into the read buffer.
+/// Blobs are read incrementally.
+#[derive(Default)]
+pub(crate) enum ReadState {
+    #[default]
+    Empty,
+    Msg {
+        msg: Received,
+        pos: usize,
+    },
+    Blob(BlobReader),
+}
+
+impl ReadState {
+    /// Whether all data has been read.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Empty => true,
+            Self::Msg { msg, pos } => *pos >= msg.len(),
+            Self::Blob(reader) => reader.is_done(),
+        }
+    }
+
+    /// Starts reading the specified message.
+    pub fn set(&mut self, mut msg: Received) {
+        *self = match msg {
+            Received::Blob(blob) => Self::Blob(BlobReader::new(blob)),
+            _ => {
+                msg.text_to_wasm();
+                Self::Msg { msg, pos: 0 }
+            }
+        };
+    }
+
+    /// Copies as much data as is available and fits into `buf`.
+    pub fn poll_read(&mut self, cx: &mut Context, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
+        match self {
+            Self::Empty => (),
+            Self::Msg { msg, pos } => {
+                let n = buf.remaining().min(msg.len() - *pos);
+                // SAFETY: the copied bytes are initialized before being marked as such.
+                unsafe {
+                    msg.copy_to(*pos, &mut buf.unfilled_mut()[..n]);
+                    buf.assume_init(n);
+                }
+                buf.advance(n);
+
+                *pos += n;
+                if *pos == msg.len() {
+                    *self = Self::Empty;
+                }
+            }
+            Self::Blob(reader) => return Pin::new(reader).poll_read(cx, buf),
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
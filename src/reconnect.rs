@@ -0,0 +1,416 @@
+//! Automatically reconnecting WebSocket.
+
+use futures_core::Stream;
+use futures_sink::Sink;
+use futures_util::{FutureExt, StreamExt};
+use std::{
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{ready, Context, Poll, Waker},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+use crate::{util::sleep, CloseCode, Closed, ClosedReason, Msg, WebSocket, WebSocketBuilder, WebSocketError};
+
+/// Exponential backoff with jitter used for reconnecting.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    /// Delay before the first reconnection attempt.
+    pub initial_delay: Duration,
+    /// Maximum delay between two reconnection attempts.
+    pub max_delay: Duration,
+    /// Factor the delay is multiplied with after each failed attempt.
+    pub factor: f64,
+    /// Fraction of the delay that is randomized, between 0 and 1.
+    ///
+    /// A jitter of 0.5 results in a delay that is randomly chosen
+    /// between 50% and 100% of the computed delay.
+    pub jitter: f64,
+    /// Maximum number of consecutive reconnection attempts.
+    ///
+    /// If `None`, reconnecting is attempted indefinitely.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            factor: 2.,
+            jitter: 0.5,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before the specified reconnection attempt, starting from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = (self.initial_delay.as_secs_f64() * self.factor.powi(exp)).min(self.max_delay.as_secs_f64());
+        let delay = delay * (1. - self.jitter.clamp(0., 1.) * js_sys::Math::random());
+        Duration::try_from_secs_f64(delay).unwrap_or(self.max_delay)
+    }
+}
+
+/// Event emitted by a [ReconnectingWebSocket].
+#[derive(Debug, Clone)]
+pub enum ReconnectEvent {
+    /// The connection has been lost.
+    Disconnected {
+        /// Reason why the connection was closed.
+        reason: ClosedReason,
+        /// Error that caused the connection to be lost, if any.
+        error: Option<WebSocketError>,
+    },
+    /// A reconnection attempt will be made after the specified delay.
+    Reconnecting {
+        /// Number of the reconnection attempt, starting from 1.
+        attempt: u32,
+        /// Delay before the connection attempt is started.
+        delay: Duration,
+    },
+    /// A reconnection attempt failed.
+    ReconnectFailed {
+        /// Number of the reconnection attempt, starting from 1.
+        attempt: u32,
+        /// Error that occurred while connecting.
+        error: WebSocketError,
+    },
+    /// The connection has been reestablished.
+    Reconnected {
+        /// Number of reconnection attempts that were necessary.
+        attempt: u32,
+    },
+    /// No further reconnection attempts will be made.
+    ///
+    /// This happens when the close reason is not considered transient
+    /// or the maximum number of reconnection attempts has been reached.
+    Stopped,
+}
+
+/// Stream of [events](ReconnectEvent) of a [ReconnectingWebSocket].
+///
+/// Obtained by calling [ReconnectingWebSocket::events].
+pub struct ReconnectEvents(mpsc::UnboundedReceiver<ReconnectEvent>);
+
+impl fmt::Debug for ReconnectEvents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ReconnectEvents").finish()
+    }
+}
+
+impl Stream for ReconnectEvents {
+    type Item = ReconnectEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.0.poll_recv(cx)
+    }
+}
+
+enum State {
+    Connected { socket: Box<WebSocket>, closed: Closed },
+    Disconnecting(Closed),
+    Waiting(Pin<Box<dyn Future<Output = ()>>>),
+    Connecting(Pin<Box<dyn Future<Output = Result<WebSocket, WebSocketError>>>>),
+    Stopped,
+}
+
+/// A WebSocket that automatically reconnects when the connection is lost.
+///
+/// The connection is reestablished using the [WebSocketBuilder] it was created from
+/// when it is closed with a [transient close code](CloseCode::is_transient).
+/// The delay between reconnection attempts is determined by the [Backoff] strategy.
+///
+/// Messages are received using the [Stream] trait and sent using the [Sink] trait
+/// across reconnects. Messages that are in flight while the connection is lost are
+/// dropped. While disconnected, sending and flushing wait until the connection has been
+/// reestablished. Once reconnecting has stopped, the stream yields the error that caused
+/// the connection to be lost, if any, and then ends.
+///
+/// The WebSocket is closed when dropped.
+pub struct ReconnectingWebSocket {
+    builder: WebSocketBuilder,
+    backoff: Backoff,
+    filter: Box<dyn Fn(&ClosedReason) -> bool>,
+    state: State,
+    attempt: u32,
+    reason: Option<ClosedReason>,
+    cause: Option<WebSocketError>,
+    error: Option<WebSocketError>,
+    events: Vec<mpsc::UnboundedSender<ReconnectEvent>>,
+    wakers: Vec<Waker>,
+}
+
+impl fmt::Debug for ReconnectingWebSocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReconnectingWebSocket")
+            .field("url", &self.builder.url)
+            .field("backoff", &self.backoff)
+            .field("connected", &self.is_connected())
+            .field("attempt", &self.attempt)
+            .finish()
+    }
+}
+
+impl ReconnectingWebSocket {
+    /// Establishes the WebSocket connection using the default [Backoff] strategy.
+    ///
+    /// Failure to establish the initial connection is reported as an error.
+    pub async fn connect(builder: WebSocketBuilder) -> Result<Self, WebSocketError> {
+        Self::connect_with_backoff(builder, Backoff::default()).await
+    }
+
+    /// Establishes the WebSocket connection using the specified [Backoff] strategy.
+    ///
+    /// Failure to establish the initial connection is reported as an error.
+    pub async fn connect_with_backoff(
+        builder: WebSocketBuilder, backoff: Backoff,
+    ) -> Result<Self, WebSocketError> {
+        let socket = builder.clone().connect().await?;
+        Ok(Self {
+            builder,
+            backoff,
+            filter: Box::new(|reason| reason.code.is_transient()),
+            state: State::Connected { closed: socket.closed(), socket: Box::new(socket) },
+            attempt: 0,
+            reason: None,
+            cause: None,
+            error: None,
+            events: Vec::new(),
+            wakers: Vec::new(),
+        })
+    }
+
+    /// Sets the function that decides whether to reconnect after the connection has been closed.
+    ///
+    /// By default, reconnecting is attempted if the close code is [transient](CloseCode::is_transient).
+    pub fn set_reconnect_filter(&mut self, filter: impl Fn(&ClosedReason) -> bool + 'static) {
+        self.filter = Box::new(filter);
+    }
+
+    /// Returns a stream of connection events.
+    ///
+    /// Only events occurring after this function has been called are returned.
+    pub fn events(&mut self) -> ReconnectEvents {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.events.push(tx);
+        ReconnectEvents(rx)
+    }
+
+    /// Whether the WebSocket is currently connected.
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, State::Connected { .. })
+    }
+
+    /// The currently connected WebSocket, if any.
+    pub fn socket(&self) -> Option<&WebSocket> {
+        match &self.state {
+            State::Connected { socket, .. } => Some(&**socket),
+            _ => None,
+        }
+    }
+
+    /// Closes the WebSocket and stops reconnecting.
+    pub fn close(self) {
+        self.close_with_reason(CloseCode::NormalClosure, "");
+    }
+
+    /// Closes the WebSocket with the specified close code and reason and stops reconnecting.
+    ///
+    /// ## Panics
+    /// Panics if the close code is neither [CloseCode::NormalClosure] nor
+    /// [CloseCode::Other] with a value between 3000 and 4999.
+    #[track_caller]
+    pub fn close_with_reason(self, code: CloseCode, reason: &str) {
+        if let State::Connected { socket, .. } = self.state {
+            (*socket).close_with_reason(code, reason);
+        } else if !code.is_valid() {
+            panic!("WebSocket close code {code} is invalid");
+        }
+    }
+
+    fn emit(&mut self, event: ReconnectEvent) {
+        self.events.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn register(&mut self, cx: &Context) {
+        if !self.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            self.wakers.push(cx.waker().clone());
+        }
+    }
+
+    /// Called when the current connection has failed, optionally because of the specified error.
+    fn connection_lost(&mut self, error: Option<WebSocketError>) {
+        match mem::replace(&mut self.state, State::Stopped) {
+            State::Connected { closed, .. } => {
+                self.cause = error;
+                self.state = State::Disconnecting(closed);
+            }
+            state => self.state = state,
+        }
+    }
+
+    fn disconnected(&mut self, reason: ClosedReason) {
+        let error = self.cause.take();
+        self.emit(ReconnectEvent::Disconnected { reason: reason.clone(), error: error.clone() });
+        self.reason = Some(reason.clone());
+
+        if (self.filter)(&reason) {
+            self.schedule_reconnect();
+        } else {
+            self.error = error.or_else(|| (!reason.was_clean).then(|| WebSocketError::Closed(reason.clone())));
+            self.stop();
+        }
+    }
+
+    fn schedule_reconnect(&mut self) {
+        self.attempt += 1;
+        if self.backoff.max_attempts.is_some_and(|max| self.attempt > max) {
+            self.stop();
+            return;
+        }
+
+        let delay = self.backoff.delay(self.attempt);
+        self.emit(ReconnectEvent::Reconnecting { attempt: self.attempt, delay });
+        self.state = State::Waiting(sleep(delay).boxed_local());
+    }
+
+    fn stop(&mut self) {
+        self.state = State::Stopped;
+        self.emit(ReconnectEvent::Stopped);
+        self.wake_all();
+    }
+
+    /// Drives reconnection until a WebSocket is available.
+    ///
+    /// Returns `None` if reconnecting has been stopped.
+    fn poll_socket(&mut self, cx: &mut Context) -> Poll<Option<&mut WebSocket>> {
+        self.drive(cx);
+
+        match &mut self.state {
+            State::Connected { socket, .. } => Poll::Ready(Some(&mut **socket)),
+            State::Stopped => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+
+    fn drive(&mut self, cx: &mut Context) {
+        loop {
+            match &mut self.state {
+                State::Connected { .. } | State::Stopped => return,
+                State::Disconnecting(closed) => match closed.poll_unpin(cx) {
+                    Poll::Ready(reason) => self.disconnected(reason),
+                    Poll::Pending => return self.register(cx),
+                },
+                State::Waiting(delay) => match delay.poll_unpin(cx) {
+                    Poll::Ready(()) => {
+                        self.state = State::Connecting(self.builder.clone().connect().boxed_local());
+                    }
+                    Poll::Pending => return self.register(cx),
+                },
+                State::Connecting(connecting) => match connecting.poll_unpin(cx) {
+                    Poll::Ready(Ok(socket)) => {
+                        self.emit(ReconnectEvent::Reconnected { attempt: self.attempt });
+                        self.attempt = 0;
+                        self.error = None;
+                        self.state = State::Connected { closed: socket.closed(), socket: Box::new(socket) };
+                        self.wake_all();
+                    }
+                    Poll::Ready(Err(err)) => {
+                        self.emit(ReconnectEvent::ReconnectFailed { attempt: self.attempt, error: err.clone() });
+                        self.error = Some(err);
+                        self.schedule_reconnect();
+                    }
+                    Poll::Pending => return self.register(cx),
+                },
+            }
+        }
+    }
+
+    fn closed_error(&self) -> WebSocketError {
+        WebSocketError::Closed(self.reason.clone().unwrap_or_else(ClosedReason::unknown))
+    }
+}
+
+impl Stream for ReconnectingWebSocket {
+    type Item = Result<Msg, WebSocketError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            let Some(socket) = ready!(self.poll_socket(cx)) else {
+                return Poll::Ready(self.error.take().map(Err));
+            };
+
+            match ready!(socket.poll_next_unpin(cx)) {
+                Some(Ok(msg)) => return Poll::Ready(Some(Ok(msg))),
+                Some(Err(err)) => self.connection_lost(Some(err)),
+                None => self.connection_lost(None),
+            }
+        }
+    }
+}
+
+impl Sink<Msg> for ReconnectingWebSocket {
+    type Error = WebSocketError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        loop {
+            let Some(socket) = ready!(self.poll_socket(cx)) else {
+                return Poll::Ready(Err(self.closed_error()));
+            };
+
+            match ready!(Sink::<Msg>::poll_ready(Pin::new(socket), cx)) {
+                Ok(()) => return Poll::Ready(Ok(())),
+                Err(err) => self.connection_lost(Some(err)),
+            }
+        }
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Msg) -> Result<(), Self::Error> {
+        let State::Connected { socket, .. } = &mut self.state else {
+            return Err(self.closed_error());
+        };
+
+        let res = Sink::<Msg>::start_send(Pin::new(&mut **socket), item);
+        if let Err(err) = &res {
+            self.connection_lost(Some(err.clone()));
+        }
+        res
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        loop {
+            let Some(socket) = ready!(self.poll_socket(cx)) else {
+                return Poll::Ready(Err(self.closed_error()));
+            };
+
+            match ready!(Sink::<Msg>::poll_flush(Pin::new(socket), cx)) {
+                Ok(()) => return Poll::Ready(Ok(())),
+                Err(err) => self.connection_lost(Some(err)),
+            }
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let res = match &mut self.state {
+            State::Connected { socket, .. } => ready!(Sink::<Msg>::poll_close(Pin::new(&mut **socket), cx)),
+            _ => Ok(()),
+        };
+
+        if !matches!(self.state, State::Stopped) {
+            self.stop();
+        }
+        Poll::Ready(res)
+    }
+}
@@ -0,0 +1,47 @@
+//! Multi-producer sending.
+
+use futures_util::SinkExt;
+use std::{fmt, rc::Rc};
+use tokio::sync::Mutex;
+
+use crate::{CloseHandle, Msg, WebSocketError, WebSocketSender};
+
+/// A cloneable sender for sending messages from multiple producers.
+///
+/// All clones share the same underlying [WebSocketSender] and thus its backpressure.
+/// Producers waiting for send buffer space are served in first-in, first-out order,
+/// so that a single producer cannot starve the others.
+/// Each message is sent as a whole and the messages of each producer are sent in order.
+///
+/// Obtained by calling [WebSocketSender::into_shared].
+#[derive(Clone)]
+pub struct SharedSender {
+    sender: Rc<Mutex<WebSocketSender>>,
+    close_handle: CloseHandle,
+}
+
+impl fmt::Debug for SharedSender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedSender").field("close_handle", &self.close_handle).finish_non_exhaustive()
+    }
+}
+
+impl SharedSender {
+    pub(crate) fn new(sender: WebSocketSender) -> Self {
+        let close_handle = sender.close_handle();
+        Self { sender: Rc::new(Mutex::new(sender)), close_handle }
+    }
+
+    /// Sends a message.
+    ///
+    /// Resolves once the message has been handed to the browser for sending.
+    pub async fn send(&self, msg: Msg) -> Result<(), WebSocketError> {
+        let mut sender = self.sender.lock().await;
+        sender.send(msg).await
+    }
+
+    /// Returns a cloneable handle for closing the WebSocket.
+    pub fn close_handle(&self) -> CloseHandle {
+        self.close_handle.clone()
+    }
+}
@@ -2,14 +2,12 @@
 
 use futures_core::Stream;
 use futures_sink::Sink;
-use futures_util::FutureExt;
-use js_sys::{Array, ArrayBuffer, Promise, Uint8Array};
+use futures_util::{future::LocalBoxFuture, task::noop_waker_ref, FutureExt};
+use js_sys::{Array, Promise, Uint8Array};
 use std::{
-    cell::{Cell, RefCell},
+    cell::{Cell, OnceCell, RefCell},
     collections::VecDeque,
     future::Future,
-    io,
-    io::{Error, ErrorKind},
     ops::Deref,
     pin::Pin,
     rc::Rc,
@@ -18,26 +16,149 @@ use std::{
 };
 use tokio::sync::watch;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::JsFuture;
-use web_sys::BinaryType;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{BinaryType, Blob};
 
 use crate::{
-    closed::Closed,
-    util::{js_err, sleep},
-    CloseCode, ClosedReason, Info, Interface, Msg, WebSocketBuilder,
+    closed::{publish_closed, Closed},
+    flow::Credits,
+    heartbeat,
+    limit::{ReceiveLimiter, ReceiveOverflowPolicy},
+    read::Received,
+    state::{publish_state, ConnectionState},
+    util::{js_err_msg, now, on_abort, sleep, AbortListener, Pause, Timer},
+    CloseInitiator, ClosedReason, ConnectionInfo, Info, Interface, Msg, WebSocketBuilder, WebSocketError,
 };
 
 const DEFAULT_SEND_BUFFER_SIZE: usize = 4_194_304;
 const DEFAULT_RECEIVE_BUFFER_SIZE: usize = 67_108_864;
 
-struct Guard {
+#[wasm_bindgen]
+extern "C" {
+    /// A WebSocket accepting any supported data type for sending.
+    type SendWebSocket;
+
+    /// Sends a string, `ArrayBuffer`, typed array or blob.
+    #[wasm_bindgen(method, catch)]
+    fn send(this: &SendWebSocket, data: &JsValue) -> Result<(), JsValue>;
+}
+
+pub(crate) struct Guard {
     socket: web_sys::WebSocket,
     closed: Cell<bool>,
+    failed: RefCell<Option<WebSocketError>>,
+    close_timeout: Option<Duration>,
+    closed_tx: watch::Sender<Option<ClosedReason>>,
+    state_tx: watch::Sender<ConnectionState>,
+    recv_queue: Rc<RecvQueue>,
+    abort_listener: OnceCell<AbortListener>,
 }
 
 impl Guard {
-    fn new(socket: web_sys::WebSocket) -> Self {
-        Self { socket, closed: Cell::new(false) }
+    fn new(
+        socket: web_sys::WebSocket, close_timeout: Option<Duration>,
+        closed_tx: watch::Sender<Option<ClosedReason>>, state_tx: watch::Sender<ConnectionState>,
+        recv_queue: Rc<RecvQueue>,
+    ) -> Self {
+        Self {
+            socket,
+            closed: Cell::new(false),
+            failed: RefCell::new(None),
+            close_timeout,
+            closed_tx,
+            state_tx,
+            recv_queue,
+            abort_listener: OnceCell::new(),
+        }
+    }
+
+    /// Marks the WebSocket as closed locally and starts the close handshake timeout.
+    fn closing(self: &Rc<Self>) {
+        if self.closed.replace(true) {
+            return;
+        }
+        publish_state(&self.state_tx, ConnectionState::Closing);
+
+        let Some(close_timeout) = self.close_timeout else { return };
+        let this = Rc::downgrade(self);
+        spawn_local(async move {
+            sleep(close_timeout).await;
+            if let Some(this) = this.upgrade() {
+                this.terminate(ClosedReason::close_timed_out(), WebSocketError::TimedOut);
+            }
+        });
+    }
+
+    /// Closes the WebSocket because of a local failure without waiting
+    /// for the close handshake to complete.
+    fn terminate(self: &Rc<Self>, reason: ClosedReason, err: WebSocketError) {
+        if publish_closed(&self.closed_tx, &self.state_tx, reason) {
+            self.fail(err);
+            self.recv_queue.close();
+        }
+    }
+
+    /// Closes the WebSocket because a receive limit has been violated.
+    fn violated(self: &Rc<Self>, reason: ClosedReason) {
+        let err = match reason.initiator {
+            CloseInitiator::LocalOverflow => WebSocketError::ReceiveOverflow(reason.clone()),
+            _ => WebSocketError::Closed(reason.clone()),
+        };
+        self.terminate(reason, err);
+    }
+
+    /// Closes the WebSocket with the specified close code and reason,
+    /// unless it is already closed or closing.
+    ///
+    /// Returns whether the WebSocket was closed by this call.
+    #[track_caller]
+    pub(crate) fn close_with_reason(self: &Rc<Self>, code: u16, reason: &str) -> bool {
+        if self.closed.get() || self.closed_tx.borrow().is_some() {
+            return false;
+        }
+
+        self.socket.close_with_code_and_reason(code, reason).unwrap();
+        self.closing();
+        true
+    }
+
+    /// Accounts for a received message and grants further flow control credits, if necessary.
+    fn grant(&self, credits: &Credits, len: usize) {
+        if let Some(grant) = credits.consumed(len) {
+            let _ = self.socket.send_with_str(&grant);
+        }
+    }
+
+    /// Whether the WebSocket has been closed locally.
+    pub(crate) fn is_closed_locally(&self) -> bool {
+        self.closed.get()
+    }
+
+    /// Receiver for the reason once the WebSocket has been closed.
+    pub(crate) fn closed_rx(&self) -> watch::Receiver<Option<ClosedReason>> {
+        self.closed_tx.subscribe()
+    }
+
+    /// Closes the WebSocket because of a local failure, such as a timeout.
+    fn fail(self: &Rc<Self>, err: WebSocketError) -> WebSocketError {
+        *self.failed.borrow_mut() = Some(err.clone());
+        if !self.closed.get() {
+            let _ = self.socket.close();
+            self.closing();
+        }
+        err
+    }
+
+    /// The local failure that caused the WebSocket to be closed, if any.
+    fn failure(&self) -> Option<WebSocketError> {
+        self.failed.borrow().clone()
+    }
+
+    /// The error reported when using the WebSocket after it has been closed.
+    fn closed_err(&self) -> WebSocketError {
+        self.failure().unwrap_or_else(|| {
+            WebSocketError::Closed(self.closed_tx.borrow().clone().unwrap_or_else(ClosedReason::unknown))
+        })
     }
 }
 
@@ -57,21 +178,32 @@ impl Drop for Guard {
 }
 
 struct RecvQueue {
-    msgs: RefCell<VecDeque<Msg>>,
+    msgs: RefCell<VecDeque<Received>>,
     waker: Cell<Option<Waker>>,
     open: Cell<bool>,
     buffered: Cell<usize>,
     buffer_limit: usize,
+    msg_limit: usize,
+    overflow_policy: ReceiveOverflowPolicy,
+    dropped: Cell<u64>,
+    limiter: ReceiveLimiter,
 }
 
 impl RecvQueue {
-    pub fn new(buffer_limit: usize) -> Self {
+    pub fn new(
+        buffer_limit: usize, msg_limit: Option<usize>, overflow_policy: ReceiveOverflowPolicy,
+        limiter: ReceiveLimiter,
+    ) -> Self {
         Self {
             msgs: RefCell::new(VecDeque::new()),
             waker: Cell::new(None),
             open: Cell::new(true),
             buffered: Cell::new(0),
             buffer_limit,
+            msg_limit: msg_limit.unwrap_or(usize::MAX),
+            overflow_policy,
+            dropped: Cell::new(0),
+            limiter,
         }
     }
 
@@ -86,24 +218,80 @@ impl RecvQueue {
         self.wake();
     }
 
-    pub fn enqueue(&self, msg: Msg) -> bool {
+    /// Enqueues a received message.
+    ///
+    /// `dropped` is called with the length of each message dropped because of the overflow policy.
+    ///
+    /// Returns the reason for closing the WebSocket if a receive limit has been violated.
+    pub fn enqueue(&self, mut msg: Received, mut dropped: impl FnMut(usize)) -> Result<(), ClosedReason> {
         if !self.open.get() {
-            return false;
+            return Ok(());
         }
 
-        let new_buffered = self.buffered.get() + msg.len();
-        if new_buffered > self.buffer_limit {
-            return false;
+        self.limiter.check(msg.len())?;
+
+        // Determining the key for coalescing requires the message in Wasm memory.
+        let msg_key = match &self.overflow_policy {
+            ReceiveOverflowPolicy::Coalesce(key) => msg.as_msg().and_then(|msg| key(msg)),
+            _ => None,
+        };
+
+        let mut msgs = self.msgs.borrow_mut();
+        let fits = |msgs: &VecDeque<Received>| {
+            self.buffered.get() + msg.len() <= self.buffer_limit && msgs.len() < self.msg_limit
+        };
+
+        if !fits(&msgs) {
+            match &self.overflow_policy {
+                ReceiveOverflowPolicy::Close => return Err(ClosedReason::receive_overflow()),
+                ReceiveOverflowPolicy::DropNewest => {
+                    self.dropped.set(self.dropped.get() + 1);
+                    dropped(msg.len());
+                    return Ok(());
+                }
+                ReceiveOverflowPolicy::DropOldest => (),
+                ReceiveOverflowPolicy::Coalesce(key) => {
+                    if let Some(msg_key) = msg_key {
+                        if let Some(pos) = msgs.iter_mut().position(|queued| {
+                            queued.as_msg().and_then(|queued| key(queued)).as_ref() == Some(&msg_key)
+                        }) {
+                            let queued = msgs.remove(pos).unwrap();
+                            self.remove(&queued);
+                            dropped(queued.len());
+                        }
+                    }
+                }
+            }
+
+            while !fits(&msgs) {
+                match msgs.pop_front() {
+                    Some(queued) => {
+                        self.remove(&queued);
+                        dropped(queued.len());
+                    }
+                    None => {
+                        self.dropped.set(self.dropped.get() + 1);
+                        dropped(msg.len());
+                        return Ok(());
+                    }
+                }
+            }
         }
 
-        self.buffered.set(new_buffered);
-        self.msgs.borrow_mut().push_back(msg);
+        self.buffered.set(self.buffered.get() + msg.len());
+        msgs.push_back(msg);
         self.wake();
 
-        true
+        Ok(())
+    }
+
+    /// Accounts for a buffered message that has been dropped.
+    fn remove(&self, msg: &Received) {
+        self.buffered.set(self.buffered.get() - msg.len());
+        self.dropped.set(self.dropped.get() + 1);
     }
 
-    pub fn dequeue(&self) -> Option<Msg> {
+    pub fn dequeue(&self) -> Option<Received> {
         let msg = self.msgs.borrow_mut().pop_front()?;
         self.buffered.set(self.buffered.get() - msg.len());
         Some(msg)
@@ -117,59 +305,98 @@ pub struct Inner {
 }
 
 impl Inner {
-    pub async fn new(builder: WebSocketBuilder) -> io::Result<(Self, Info)> {
+    pub async fn new(builder: WebSocketBuilder) -> Result<(Self, Info), WebSocketError> {
+        let connect_started = now();
+
         // Create WebSocket.
         let protocols = Array::new();
         for proto in builder.protocols {
             protocols.push(&JsValue::from_str(&proto));
         }
+        let recv_queue = Rc::new(RecvQueue::new(
+            builder.receive_buffer_size.unwrap_or(DEFAULT_RECEIVE_BUFFER_SIZE),
+            builder.receive_buffer_messages,
+            builder.receive_overflow_policy.clone(),
+            ReceiveLimiter::new(builder.max_message_size, builder.max_message_rate),
+        ));
+        let (closed_tx, closed_rx) = watch::channel(None);
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connecting);
         let socket = Rc::new(Guard::new(
             web_sys::WebSocket::new_with_str_sequence(&builder.url, &protocols)
-                .map_err(|err| js_err(ErrorKind::InvalidInput, &err))?,
+                .map_err(|err| WebSocketError::InvalidUrl(js_err_msg(&err).unwrap_or_default()))?,
+            builder.close_timeout,
+            closed_tx.clone(),
+            state_tx.clone(),
+            recv_queue.clone(),
         ));
-        socket.set_binary_type(BinaryType::Arraybuffer);
-
-        // Setup receive queue.
-        let recv_queue =
-            Rc::new(RecvQueue::new(builder.receive_buffer_size.unwrap_or(DEFAULT_RECEIVE_BUFFER_SIZE)));
-        let (closed_tx, closed_rx) = watch::channel(None);
+        socket.set_binary_type(if builder.receive_blobs { BinaryType::Blob } else { BinaryType::Arraybuffer });
+
+        // Close WebSocket when aborted.
+        if let Some(signal) = &builder.abort_signal {
+            let weak = Rc::downgrade(&socket);
+            let listener = on_abort(signal, move || {
+                if let Some(socket) = weak.upgrade() {
+                    socket.fail(WebSocketError::Aborted);
+                }
+            });
+            let _ = socket.abort_listener.set(listener);
+        }
 
         // Setup close handler.
         let on_close = {
+            let socket = Rc::downgrade(&socket);
             let recv_queue = recv_queue.clone();
             let closed_tx = closed_tx.clone();
+            let state_tx = state_tx.clone();
             Closure::wrap(Box::new(move |event: web_sys::CloseEvent| {
-                closed_tx.send_replace(Some(ClosedReason {
-                    code: event.code().into(),
-                    reason: event.reason(),
-                    was_clean: event.was_clean(),
-                }));
+                let closed_locally = socket.upgrade().is_none_or(|socket| socket.is_closed_locally());
+                publish_closed(
+                    &closed_tx,
+                    &state_tx,
+                    ClosedReason::from_browser(event.code(), event.reason(), event.was_clean(), closed_locally),
+                );
                 recv_queue.close();
             }) as Box<dyn Fn(_)>)
         };
         socket.set_onclose(Some(on_close.into_js_value().unchecked_ref()));
 
+        // Setup heartbeat monitor.
+        let heartbeat = builder
+            .heartbeat
+            .clone()
+            .map(|heartbeat| Rc::new(heartbeat::Monitor::new(heartbeat, true, closed_rx.clone())));
+
+        // Setup flow control.
+        let credits = builder.flow_control.map(|flow_control| Rc::new(Credits::new(flow_control)));
+
         // Setup message receive handler.
         let on_msg = {
+            let socket = Rc::downgrade(&socket);
             let recv_queue = recv_queue.clone();
+            let heartbeat = heartbeat.clone();
+            let credits = credits.clone();
+            let receive_mode = builder.receive_mode;
             Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
-                let msg = {
-                    let data = event.data();
-                    if let Some(buf) = data.dyn_ref::<ArrayBuffer>() {
-                        Msg::Binary(js_sys::Uint8Array::new(buf).to_vec())
-                    } else if let Some(text) = data.as_string() {
-                        Msg::Text(text)
-                    } else {
-                        unreachable!("received event with unknown data type");
+                let mut msg = Received::from_js(&event.data(), receive_mode);
+                if heartbeat
+                    .as_ref()
+                    .is_some_and(|heartbeat| msg.as_msg().is_some_and(|msg| heartbeat.is_pong(msg)))
+                {
+                    if let (Some(socket), Some(credits)) = (socket.upgrade(), &credits) {
+                        socket.grant(credits, msg.len());
+                    }
+                    return;
+                }
+                // Credits for dropped messages are returned, since the application never receives them.
+                let res = recv_queue.enqueue(msg, |len| {
+                    if let (Some(socket), Some(credits)) = (socket.upgrade(), &credits) {
+                        socket.grant(credits, len);
+                    }
+                });
+                if let Err(reason) = res {
+                    if let Some(socket) = socket.upgrade() {
+                        socket.violated(reason);
                     }
-                };
-                if !recv_queue.enqueue(msg) {
-                    closed_tx.send_replace(Some(ClosedReason {
-                        code: CloseCode::MessageTooBig,
-                        reason: "receive buffer overflow".to_string(),
-                        was_clean: false,
-                    }));
-                    recv_queue.close();
                 }
             }) as Box<dyn Fn(_)>)
         };
@@ -180,67 +407,122 @@ impl Inner {
             socket.set_onopen(Some(&resolve));
             socket.set_onerror(Some(&reject));
         });
-        JsFuture::from(connect).await.map_err(|err| js_err(ErrorKind::ConnectionRefused, &err))?;
+        JsFuture::from(connect)
+            .await
+            .map_err(|err| WebSocketError::ConnectFailed(js_err_msg(&err).unwrap_or_default()))?;
+        let opened = now();
+        publish_state(&state_tx, ConnectionState::Open);
+
+        // Grant initial flow control credits.
+        if let Some(credits) = &credits {
+            let _ = socket.send_with_str(&credits.initial());
+        }
+
+        // Start heartbeat.
+        if let Some(heartbeat) = heartbeat {
+            let socket = Rc::downgrade(&socket);
+            spawn_local(async move {
+                let dead = heartbeat
+                    .run(|ping| {
+                        let Some(socket) = socket.upgrade() else { return };
+                        let _ = match ping.dyn_ref::<Uint8Array>() {
+                            Some(array) => socket.send_with_js_u8_array(array),
+                            None => socket.send_with_str(&ping.as_string().unwrap()),
+                        };
+                    })
+                    .await;
+                if !dead {
+                    return;
+                }
+                if let Some(socket) = socket.upgrade() {
+                    socket.terminate(ClosedReason::heartbeat_timed_out(), WebSocketError::TimedOut);
+                }
+            });
+        }
 
         Ok((
             Self {
-                sender: Sender::new(socket.clone(), builder.send_buffer_size),
-                receiver: Receiver::new(socket.clone(), recv_queue, closed_rx.clone()),
+                sender: Sender::new(socket.clone(), builder.send_buffer_size, builder.send_timeout),
+                receiver: Receiver::new(
+                    socket.clone(),
+                    recv_queue,
+                    closed_rx.clone(),
+                    builder.receive_timeout,
+                    credits,
+                ),
                 closed_rx,
             },
-            Info { url: builder.url, protocol: socket.protocol(), interface: Interface::Standard },
+            Info {
+                connection: ConnectionInfo {
+                    url: socket.url(),
+                    protocol: socket.protocol(),
+                    extensions: socket.extensions(),
+                    interface: Interface::Standard,
+                    connect_started,
+                    opened,
+                },
+                state_rx,
+            },
         ))
     }
 
     pub fn closed(&self) -> Closed {
-        let mut closed_rx = self.closed_rx.clone();
-        Closed(
-            async move {
-                match closed_rx.wait_for(|c| c.is_some()).await {
-                    Ok(reason) => reason.clone().unwrap(),
-                    Err(_) => {
-                        ClosedReason { code: CloseCode::AbnormalClosure, reason: String::new(), was_clean: false }
-                    }
-                }
-            }
-            .boxed_local(),
-        )
+        Closed::new(self.closed_rx.clone())
     }
 
     pub fn into_split(self) -> (Sender, Receiver) {
         (self.sender, self.receiver)
     }
+
+    pub fn from_split(sender: Sender, receiver: Receiver) -> Self {
+        let closed_rx = sender.socket.closed_tx.subscribe();
+        Self { sender, receiver, closed_rx }
+    }
 }
 
 pub struct Sender {
     socket: Rc<Guard>,
     send_buffer_size: usize,
-    writing: Option<Pin<Box<dyn Future<Output = io::Result<()>>>>>,
+    send_timeout: Option<Duration>,
+    writing: Option<LocalBoxFuture<'static, Result<(), WebSocketError>>>,
 }
 
 impl Sender {
-    fn new(socket: Rc<Guard>, send_buffer_size: Option<usize>) -> Self {
-        Self { socket, send_buffer_size: send_buffer_size.unwrap_or(DEFAULT_SEND_BUFFER_SIZE), writing: None }
+    fn new(socket: Rc<Guard>, send_buffer_size: Option<usize>, send_timeout: Option<Duration>) -> Self {
+        Self {
+            socket,
+            send_buffer_size: send_buffer_size.unwrap_or(DEFAULT_SEND_BUFFER_SIZE),
+            send_timeout,
+            writing: None,
+        }
+    }
+
+    pub(crate) fn guard(&self) -> &Rc<Guard> {
+        &self.socket
     }
 
     #[track_caller]
     pub fn close(self, code: u16, reason: &str) {
-        self.socket.close_with_code_and_reason(code, reason).unwrap();
-        self.socket.closed.set(true);
+        self.socket.close_with_reason(code, reason);
     }
 
-    fn wait_for_buffered_amount(&self, max_amount: usize) -> impl Future<Output = io::Result<()>> {
+    fn wait_for_buffered_amount(&self, max_amount: usize) -> impl Future<Output = Result<(), WebSocketError>> {
         let socket = self.socket.clone();
+        let deadline = self.send_timeout.map(|timeout| now() + timeout.as_secs_f64() * 1000.);
         async move {
             loop {
                 if socket.ready_state() != web_sys::WebSocket::OPEN {
-                    return Err(Error::new(ErrorKind::ConnectionReset, "WebSocket not open"));
+                    return Err(socket.closed_err());
                 }
 
                 if usize::try_from(socket.buffered_amount()).unwrap() <= max_amount {
                     return Ok(());
                 }
 
+                if deadline.is_some_and(|deadline| now() >= deadline) {
+                    return Err(socket.fail(WebSocketError::TimedOut));
+                }
+
                 sleep(Duration::ZERO).await;
             }
         }
@@ -248,7 +530,7 @@ impl Sender {
 }
 
 impl Sink<&JsValue> for Sender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         if self.writing.is_none() {
@@ -267,14 +549,7 @@ impl Sink<&JsValue> for Sender {
             panic!("WebSocket not ready for sending");
         }
 
-        if let Some(array) = item.dyn_ref::<Uint8Array>() {
-            self.socket.send_with_js_u8_array(array)
-        } else if let Some(str) = item.as_string() {
-            self.socket.send_with_str(&str)
-        } else {
-            unreachable!()
-        }
-        .map_err(|err| js_err(ErrorKind::ConnectionReset, &err))?;
+        self.socket.unchecked_ref::<SendWebSocket>().send(item).map_err(|err| WebSocketError::js(&err))?;
 
         Ok(())
     }
@@ -285,7 +560,8 @@ impl Sink<&JsValue> for Sender {
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         ready!(self.as_mut().poll_ready(cx))?;
-        let res = self.socket.close().map_err(|err| js_err(ErrorKind::ConnectionReset, &err));
+        let res = self.socket.close().map_err(|err| WebSocketError::js(&err));
+        self.socket.closing();
         Poll::Ready(res)
     }
 }
@@ -297,38 +573,181 @@ impl Drop for Sender {
 }
 
 pub struct Receiver {
-    _socket: Rc<Guard>,
+    socket: Rc<Guard>,
     queue: Rc<RecvQueue>,
     closed_rx: watch::Receiver<Option<ClosedReason>>,
+    idle: Timer,
+    credits: Option<Rc<Credits>>,
+    pause: Pause,
+    peeked: Option<Received>,
+    reading_blob: Option<(Blob, JsFuture)>,
 }
 
 impl Receiver {
-    fn new(socket: Rc<Guard>, queue: Rc<RecvQueue>, closed_rx: watch::Receiver<Option<ClosedReason>>) -> Self {
-        Self { _socket: socket, queue, closed_rx }
+    fn new(
+        socket: Rc<Guard>, queue: Rc<RecvQueue>, closed_rx: watch::Receiver<Option<ClosedReason>>,
+        receive_timeout: Option<Duration>, credits: Option<Rc<Credits>>,
+    ) -> Self {
+        let mut idle = Timer::new(receive_timeout);
+        idle.start();
+        Self {
+            socket,
+            queue,
+            closed_rx,
+            idle,
+            credits,
+            pause: Pause::default(),
+            peeked: None,
+            reading_blob: None,
+        }
     }
-}
 
-impl Stream for Receiver {
-    type Item = io::Result<Msg>;
+    /// Stops delivering received messages until resumed.
+    pub fn pause(&mut self) {
+        self.pause.pause();
+    }
+
+    /// Resumes delivering received messages.
+    pub fn resume(&mut self) {
+        if self.pause.is_paused() {
+            self.idle.start();
+        }
+        self.pause.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Receives a message if one is available without waiting.
+    ///
+    /// Fails with [WebSocketError::Closed] once the WebSocket has been closed
+    /// and all messages have been received.
+    pub fn try_recv(&mut self) -> Result<Option<Msg>, WebSocketError> {
+        match self.poll_recv_msg(&mut Context::from_waker(noop_waker_ref())) {
+            Poll::Ready(Some(res)) => res.map(Some),
+            Poll::Ready(None) => Err(self.socket.closed_err()),
+            Poll::Pending => Ok(None),
+        }
+    }
+
+    /// Returns the next message if one is available without waiting, but does not remove it.
+    pub fn peek(&mut self) -> Result<Option<&Msg>, WebSocketError> {
+        if self.peeked.is_none() {
+            self.peeked = self.try_recv()?.map(Received::Msg);
+        }
+        Ok(self.peeked.as_mut().and_then(Received::as_msg))
+    }
+
+    /// Returns a received message, so that it is returned by the next receive.
+    pub(crate) fn unrecv(&mut self, msg: Received) {
+        debug_assert!(self.peeked.is_none());
+        self.peeked = Some(msg);
+    }
+
+    /// Number of messages in the receive buffer.
+    pub fn queued_len(&self) -> usize {
+        self.queue.msgs.borrow().len()
+            + usize::from(self.peeked.is_some())
+            + usize::from(self.reading_blob.is_some())
+    }
+
+    /// Size in bytes of the messages in the receive buffer.
+    pub fn queued_bytes(&self) -> usize {
+        self.queue.buffered.get()
+            + self.peeked.as_ref().map_or(0, Received::len)
+            + self.reading_blob.as_ref().map_or(0, |(blob, _)| blob.size() as usize)
+    }
+
+    pub(crate) fn guard(&self) -> &Rc<Guard> {
+        &self.socket
+    }
+
+    /// Number of received messages dropped by the receive overflow policy.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped.get()
+    }
+
+    /// Polls for the next received message without copying binary data into Wasm memory.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context) -> Poll<Option<Result<Received, WebSocketError>>> {
+        ready!(self.pause.poll_resumed(cx));
+
+        if let Some((blob, _)) = self.reading_blob.take() {
+            return Poll::Ready(Some(Ok(Received::Blob(blob))));
+        }
+
+        if let Some(msg) = self.peeked.take() {
+            return Poll::Ready(Some(Ok(msg)));
+        }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         if let Some(msg) = self.queue.dequeue() {
+            self.idle.start();
+            if let Some(credits) = &self.credits {
+                self.socket.grant(credits, msg.len());
+            }
             return Poll::Ready(Some(Ok(msg)));
         }
 
         if !self.queue.open.get() {
+            if let Some(err) = self.socket.failure() {
+                return Poll::Ready(Some(Err(err)));
+            }
+
             return match &*self.closed_rx.borrow() {
                 Some(reason) if reason.was_clean => Poll::Ready(None),
-                Some(reason) => {
-                    Poll::Ready(Some(Err(Error::new(ErrorKind::ConnectionReset, reason.reason.clone()))))
-                }
-                None => Poll::Ready(Some(Err(Error::new(ErrorKind::ConnectionReset, "WebSocket closed")))),
+                Some(reason) => Poll::Ready(Some(Err(WebSocketError::Closed(reason.clone())))),
+                None => Poll::Ready(Some(Err(WebSocketError::Closed(ClosedReason::unknown())))),
             };
         }
 
         self.queue.waker.set(Some(cx.waker().clone()));
+
+        if self.idle.poll_expired(cx).is_ready() {
+            return Poll::Ready(Some(Err(self.socket.fail(WebSocketError::TimedOut))));
+        }
+
         Poll::Pending
     }
+
+    /// Polls for the next received message, reading blobs into JavaScript memory.
+    ///
+    /// A blob is kept until it has been read, thus no message is lost when
+    /// polling is not continued.
+    pub(crate) fn poll_recv_loaded(
+        &mut self, cx: &mut Context,
+    ) -> Poll<Option<Result<Received, WebSocketError>>> {
+        loop {
+            if let Some((_, reading)) = &mut self.reading_blob {
+                let res = ready!(reading.poll_unpin(cx));
+                self.reading_blob = None;
+                return Poll::Ready(Some(
+                    res.map(|buffer| Received::Binary(Uint8Array::new(&buffer)))
+                        .map_err(|err| WebSocketError::js(&err)),
+                ));
+            }
+
+            match ready!(self.poll_recv(cx)) {
+                Some(Ok(Received::Blob(blob))) => {
+                    let reading = JsFuture::from(blob.array_buffer());
+                    self.reading_blob = Some((blob, reading));
+                }
+                res => return Poll::Ready(res),
+            }
+        }
+    }
+
+    /// Polls for the next received message, reading blobs into Wasm memory.
+    fn poll_recv_msg(&mut self, cx: &mut Context) -> Poll<Option<Result<Msg, WebSocketError>>> {
+        self.poll_recv_loaded(cx).map(|res| res.map(|res| res.map(Received::into_msg)))
+    }
+}
+
+impl Stream for Receiver {
+    type Item = Result<Msg, WebSocketError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.poll_recv_msg(cx)
+    }
 }
 
 impl Drop for Receiver {
@@ -0,0 +1,84 @@
+//! Connection state.
+
+use futures_core::Stream;
+use futures_util::{stream, StreamExt};
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::watch;
+
+/// State of a WebSocket connection.
+///
+/// The state only ever advances in the order of declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConnectionState {
+    /// The connection is being established.
+    Connecting,
+    /// The connection is established and ready to communicate.
+    Open,
+    /// The connection has been closed locally and the close handshake is in progress.
+    Closing,
+    /// The connection is closed.
+    Closed,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Connecting => write!(f, "connecting"),
+            Self::Open => write!(f, "open"),
+            Self::Closing => write!(f, "closing"),
+            Self::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// Advances the connection state, unless it is already at or beyond the specified state.
+///
+/// Returns whether the state was changed.
+pub(crate) fn publish_state(state_tx: &watch::Sender<ConnectionState>, state: ConnectionState) -> bool {
+    state_tx.send_if_modified(|current| {
+        if state > *current {
+            *current = state;
+            true
+        } else {
+            false
+        }
+    })
+}
+
+/// A stream of connection state changes.
+///
+/// It first yields the current state and then each subsequent change.
+/// It ends after yielding [ConnectionState::Closed].
+pub struct StateChanges(Pin<Box<dyn Stream<Item = ConnectionState>>>);
+
+impl StateChanges {
+    pub(crate) fn new(mut state_rx: watch::Receiver<ConnectionState>) -> Self {
+        state_rx.mark_changed();
+        Self(
+            stream::unfold(Some(state_rx), |state_rx| async move {
+                let mut state_rx = state_rx?;
+                state_rx.changed().await.ok()?;
+                let state = *state_rx.borrow_and_update();
+                Some((state, (state != ConnectionState::Closed).then_some(state_rx)))
+            })
+            .boxed_local(),
+        )
+    }
+}
+
+impl fmt::Debug for StateChanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("StateChanges").finish()
+    }
+}
+
+impl Stream for StateChanges {
+    type Item = ConnectionState;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
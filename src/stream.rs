@@ -2,35 +2,42 @@
 
 use futures_core::Stream;
 use futures_sink::Sink;
-use futures_util::FutureExt;
-use js_sys::{Array, Object, Promise, Reflect, Uint8Array};
+use futures_util::{task::noop_waker_ref, FutureExt};
+use js_sys::{Array, Object, Promise, Reflect};
 use std::{
-    cell::Cell,
-    io,
-    io::ErrorKind,
+    cell::{Cell, OnceCell, RefCell},
+    collections::VecDeque,
+    future::poll_fn,
     ops::Deref,
     pin::Pin,
-    rc::Rc,
-    task::{ready, Context, Poll},
+    rc::{Rc, Weak},
+    task::{ready, Context, Poll, Waker},
+    time::Duration,
 };
+use tokio::sync::watch;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_futures::{future_to_promise, spawn_local, JsFuture};
 use web_sys::{
-    ReadableStream, ReadableStreamDefaultReader, ReadableStreamReadResult, WritableStream,
+    Blob, ReadableStream, ReadableStreamDefaultReader, ReadableStreamReadResult, WritableStream,
     WritableStreamDefaultWriter,
 };
 
 use crate::{
-    closed::{CloseCode, Closed, ClosedReason},
-    util::{js_err, js_err_msg},
-    Info, Interface, Msg, WebSocketBuilder,
+    closed::{publish_closed, Closed, ClosedReason},
+    flow::Credits,
+    heartbeat,
+    limit::ReceiveLimiter,
+    read::Received,
+    state::{publish_state, ConnectionState},
+    util::{js_err_msg, now, on_abort, sleep, uint8_array_for_api, AbortListener, Pause, Timer},
+    ConnectionInfo, Info, Interface, Msg, ReceiveMode, WebSocketBuilder, WebSocketError,
 };
 
 const DEFAULT_SEND_BUFFER_SIZE: usize = 4_194_304;
 
 #[wasm_bindgen]
 extern "C" {
-    type WebSocketStream;
+    pub(crate) type WebSocketStream;
 
     #[wasm_bindgen(constructor, catch)]
     fn new(url: &str, options: &JsValue) -> Result<WebSocketStream, JsValue>;
@@ -76,14 +83,115 @@ extern "C" {
     fn reason(this: &WebSocketStreamClosed) -> String;
 }
 
-struct Guard {
+pub(crate) struct Guard {
     socket: WebSocketStream,
     closed: Cell<bool>,
+    failed: RefCell<Option<WebSocketError>>,
+    close_timeout: Option<Duration>,
+    closed_tx: watch::Sender<Option<ClosedReason>>,
+    state_tx: watch::Sender<ConnectionState>,
+    reader: OnceCell<ReadableStreamDefaultReader>,
+    abort_listener: OnceCell<AbortListener>,
 }
 
 impl Guard {
-    fn new(socket: WebSocketStream) -> Self {
-        Self { socket, closed: Cell::new(false) }
+    fn new(
+        socket: WebSocketStream, close_timeout: Option<Duration>, closed_tx: watch::Sender<Option<ClosedReason>>,
+        state_tx: watch::Sender<ConnectionState>,
+    ) -> Self {
+        Self {
+            socket,
+            closed: Cell::new(false),
+            failed: RefCell::new(None),
+            close_timeout,
+            closed_tx,
+            state_tx,
+            reader: OnceCell::new(),
+            abort_listener: OnceCell::new(),
+        }
+    }
+
+    /// Marks the WebSocket as closed locally and starts the close handshake timeout.
+    fn closing(self: &Rc<Self>) {
+        if self.closed.replace(true) {
+            return;
+        }
+        publish_state(&self.state_tx, ConnectionState::Closing);
+
+        let Some(close_timeout) = self.close_timeout else { return };
+        let this = Rc::downgrade(self);
+        spawn_local(async move {
+            sleep(close_timeout).await;
+            if let Some(this) = this.upgrade() {
+                this.terminate(ClosedReason::close_timed_out(), WebSocketError::TimedOut);
+            }
+        });
+    }
+
+    /// Closes the WebSocket because of a local failure without waiting
+    /// for the close handshake to complete.
+    fn terminate(self: &Rc<Self>, reason: ClosedReason, err: WebSocketError) {
+        if publish_closed(&self.closed_tx, &self.state_tx, reason) {
+            self.fail(err);
+            if let Some(reader) = self.reader.get() {
+                let _ = reader.cancel();
+            }
+        }
+    }
+
+    /// Closes the WebSocket with the specified close code and reason,
+    /// unless it is already closed or closing.
+    ///
+    /// Returns whether the WebSocket was closed by this call.
+    #[track_caller]
+    pub(crate) fn close_with_reason(self: &Rc<Self>, code: u16, reason: &str) -> bool {
+        if self.closed.get() || self.closed_tx.borrow().is_some() {
+            return false;
+        }
+
+        let options = Object::new();
+        Reflect::set(&options, &JsValue::from("closeCode"), &JsValue::from(code)).unwrap();
+        Reflect::set(&options, &JsValue::from("reason"), &JsValue::from_str(reason)).unwrap();
+        self.socket.close(&options).unwrap();
+        self.closing();
+        true
+    }
+
+    /// Whether the WebSocket has been closed locally.
+    pub(crate) fn is_closed_locally(&self) -> bool {
+        self.closed.get()
+    }
+
+    /// Future that resolves once the WebSocket has been closed.
+    pub(crate) fn wait_closed(&self) -> Closed {
+        Closed::new(self.closed_tx.subscribe())
+    }
+
+    /// Receiver for the reason once the WebSocket has been closed.
+    pub(crate) fn closed_rx(&self) -> watch::Receiver<Option<ClosedReason>> {
+        self.closed_tx.subscribe()
+    }
+
+    /// Closes the WebSocket because of a local failure, such as a timeout.
+    fn fail(self: &Rc<Self>, err: WebSocketError) -> WebSocketError {
+        *self.failed.borrow_mut() = Some(err.clone());
+        if !self.closed.get() {
+            let _ = self.socket.close(&JsValue::null());
+            self.closing();
+        }
+        err
+    }
+
+    /// The local failure that caused the WebSocket to be closed, if any.
+    fn failure(&self) -> Option<WebSocketError> {
+        self.failed.borrow().clone()
+    }
+
+    /// The error reported when using the WebSocket after it has been closed.
+    fn closed_err(&self) -> WebSocketError {
+        self.failure().unwrap_or_else(|| {
+            WebSocketError::Closed(self.closed_tx.borrow().clone().unwrap_or_else(ClosedReason::unknown))
+        })
     }
 }
 
@@ -103,13 +211,15 @@ impl Drop for Guard {
 }
 
 pub struct Inner {
-    socket: Rc<Guard>,
     pub(crate) sender: Sender,
     pub(crate) receiver: Receiver,
+    closed_rx: watch::Receiver<Option<ClosedReason>>,
 }
 
 impl Inner {
-    pub async fn new(builder: WebSocketBuilder) -> io::Result<(Self, Info)> {
+    pub async fn new(builder: WebSocketBuilder) -> Result<(Self, Info), WebSocketError> {
+        let connect_started = now();
+
         // Create WebSocketStream.
         let options = Object::new();
         if !builder.protocols.is_empty() {
@@ -119,121 +229,420 @@ impl Inner {
             }
             Reflect::set(&options, &JsValue::from_str("protocols"), &arr).unwrap();
         }
-        let socket = WebSocketStream::new(&builder.url, &JsValue::from(options))
-            .map_err(|js| js_err(ErrorKind::ConnectionRefused, &js))?;
-        let socket = Rc::new(Guard::new(socket));
+        if let Some(signal) = &builder.abort_signal {
+            Reflect::set(&options, &JsValue::from_str("signal"), signal).unwrap();
+        }
+        let socket =
+            WebSocketStream::new(&builder.url, &JsValue::from(options)).map_err(
+                |err| match WebSocketError::js(&err) {
+                    WebSocketError::Js { name, message, .. } if name == "SyntaxError" => {
+                        WebSocketError::InvalidUrl(message)
+                    }
+                    err => err,
+                },
+            )?;
+        let (closed_tx, closed_rx) = watch::channel(None);
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connecting);
+        let socket = Rc::new(Guard::new(socket, builder.close_timeout, closed_tx.clone(), state_tx.clone()));
+
+        // Close WebSocket when aborted after connection has been established.
+        if let Some(signal) = &builder.abort_signal {
+            let weak = Rc::downgrade(&socket);
+            let listener = on_abort(signal, move || {
+                if let Some(socket) = weak.upgrade() {
+                    socket.fail(WebSocketError::Aborted);
+                }
+            });
+            let _ = socket.abort_listener.set(listener);
+        }
+
+        // Monitor closing.
+        let closed = socket.closed();
+        let closed_socket = Rc::downgrade(&socket);
+        let closed_state_tx = state_tx.clone();
+        spawn_local(async move {
+            let res = JsFuture::from(closed).await;
+            let closed_locally = closed_socket.upgrade().is_none_or(|socket| socket.is_closed_locally());
+            let reason = match res {
+                Ok(c) => {
+                    let c: WebSocketStreamClosed = c.unchecked_into();
+                    ClosedReason::from_browser(c.closeCode() as u16, c.reason(), true, closed_locally)
+                }
+                Err(_) => ClosedReason::from_browser(0, String::new(), false, closed_locally),
+            };
+            publish_closed(&closed_tx, &closed_state_tx, reason);
+        });
 
         // Open WebSocket connection.
         let opened = match socket.opened().await {
             Ok(opened) => opened,
-            Err(js) => return Err(js_err(ErrorKind::ConnectionRefused, &js)),
+            Err(err) => return Err(WebSocketError::ConnectFailed(js_err_msg(&err).unwrap_or_default())),
         };
+        let opened_at = now();
+        publish_state(&state_tx, ConnectionState::Open);
 
         // Obtain reader and writer.
-        let writer = opened.writable().get_writer().unwrap();
+        let writer = Writer::new(opened.writable().get_writer().unwrap());
         let reader = opened.readable().get_reader().dyn_into::<ReadableStreamDefaultReader>().unwrap();
+        let _ = socket.reader.set(reader.clone());
+
+        // Grant initial flow control credits.
+        let credits = builder.flow_control.map(|flow_control| {
+            let credits = Rc::new(Credits::new(flow_control));
+            writer.write_detached(&JsValue::from_str(&credits.initial()));
+            (credits, writer.clone())
+        });
+
+        // Start heartbeat.
+        let heartbeat = builder
+            .heartbeat
+            .clone()
+            .map(|heartbeat| Rc::new(heartbeat::Monitor::new(heartbeat, false, closed_rx.clone())));
+        if let Some(heartbeat) = heartbeat.clone() {
+            let socket = Rc::downgrade(&socket);
+            let writer = writer.clone();
+            spawn_local(async move {
+                let dead = heartbeat.run(|ping| writer.write_detached(ping)).await;
+                if !dead {
+                    return;
+                }
+                if let Some(socket) = socket.upgrade() {
+                    socket.terminate(ClosedReason::heartbeat_timed_out(), WebSocketError::TimedOut);
+                }
+            });
+        }
 
         Ok((
             Self {
-                socket: socket.clone(),
-                sender: Sender::new(socket.clone(), writer, builder.send_buffer_size),
-                receiver: Receiver::new(socket.clone(), reader),
+                sender: Sender::new(
+                    socket.clone(),
+                    writer,
+                    builder.send_buffer_size,
+                    builder.send_timeout,
+                    builder.close_timeout,
+                ),
+                receiver: Receiver::new(
+                    socket.clone(),
+                    reader,
+                    builder.receive_timeout,
+                    heartbeat,
+                    ReceiveLimiter::new(builder.max_message_size, builder.max_message_rate),
+                    credits,
+                    builder.read_prefetch,
+                    builder.receive_mode,
+                ),
+                closed_rx,
+            },
+            Info {
+                connection: ConnectionInfo {
+                    url: socket.url(),
+                    protocol: opened.protocol(),
+                    extensions: opened.extensions(),
+                    interface: Interface::Stream,
+                    connect_started,
+                    opened: opened_at,
+                },
+                state_rx,
             },
-            Info { url: socket.url(), protocol: opened.protocol(), interface: Interface::Stream },
         ))
     }
 
     pub fn closed(&self) -> Closed {
-        let closed = self.socket.closed();
-        Closed(
-            async move {
-                match JsFuture::from(closed).await {
-                    Ok(c) => {
-                        let c: WebSocketStreamClosed = c.unchecked_into();
-                        ClosedReason {
-                            code: CloseCode::from(c.closeCode() as u16),
-                            reason: c.reason(),
-                            was_clean: true,
-                        }
+        Closed::new(self.closed_rx.clone())
+    }
+
+    pub fn into_split(self) -> (Sender, Receiver) {
+        (self.sender, self.receiver)
+    }
+
+    pub fn from_split(sender: Sender, receiver: Receiver) -> Self {
+        let closed_rx = sender.socket.closed_tx.subscribe();
+        Self { sender, receiver, closed_rx }
+    }
+}
+
+/// Binary message data owned by the sender.
+pub(crate) enum Payload {
+    Vec(Vec<u8>),
+    #[cfg(feature = "bytes")]
+    Bytes(bytes::Bytes),
+}
+
+impl Deref for Payload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Vec(vec) => vec,
+            #[cfg(feature = "bytes")]
+            Self::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+impl From<Vec<u8>> for Payload {
+    fn from(vec: Vec<u8>) -> Self {
+        Self::Vec(vec)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl From<bytes::Bytes> for Payload {
+    fn from(bytes: bytes::Bytes) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+/// A chunk waiting to be written.
+enum Chunk {
+    /// Data residing in JavaScript memory.
+    Js(JsValue),
+    /// A blob, which must be read before writing it, since WebSocketStream does not accept blobs.
+    Blob(Blob),
+    /// Data residing in Wasm memory, which is written as a view into it.
+    Lent(Payload),
+}
+
+/// Writes chunks to a WebSocketStream in order.
+///
+/// The writable stream passes a chunk on to WebSocketStream, which copies it, immediately if
+/// no write is pending and otherwise once all preceding writes have completed. Since growing
+/// Wasm memory detaches all views into it, data in Wasm memory is only written once no write
+/// is pending. Until then it is held back, together with all chunks following it.
+struct Writer {
+    writer: WritableStreamDefaultWriter,
+    queue: RefCell<VecDeque<Chunk>>,
+    pending: Cell<usize>,
+    reading_blob: Cell<bool>,
+    failed: RefCell<Option<JsValue>>,
+    waker: Cell<Option<Waker>>,
+    keep_alive: RefCell<Option<Rc<Writer>>>,
+    on_written: Closure<dyn FnMut(JsValue)>,
+    on_failed: Closure<dyn FnMut(JsValue)>,
+}
+
+impl Writer {
+    fn new(writer: WritableStreamDefaultWriter) -> Rc<Self> {
+        Rc::new_cyclic(|this: &Weak<Self>| {
+            let on_written = {
+                let this = this.clone();
+                Closure::new(move |_: JsValue| {
+                    if let Some(this) = this.upgrade() {
+                        this.settled();
+                    }
+                })
+            };
+            let on_failed = {
+                let this = this.clone();
+                Closure::new(move |err: JsValue| {
+                    if let Some(this) = this.upgrade() {
+                        this.failed.borrow_mut().get_or_insert(err);
+                        this.settled();
                     }
-                    Err(err) => ClosedReason {
-                        code: CloseCode::AbnormalClosure,
-                        reason: js_err_msg(&err).unwrap_or_default(),
-                        was_clean: false,
-                    },
+                })
+            };
+            Self {
+                writer,
+                queue: RefCell::new(VecDeque::new()),
+                pending: Cell::new(0),
+                reading_blob: Cell::new(false),
+                failed: RefCell::new(None),
+                waker: Cell::new(None),
+                keep_alive: RefCell::new(None),
+                on_written,
+                on_failed,
+            }
+        })
+    }
+
+    /// Queues a chunk and writes as many queued chunks as possible.
+    fn write(self: &Rc<Self>, chunk: Chunk) {
+        self.queue.borrow_mut().push_back(chunk);
+        self.advance();
+    }
+
+    /// Writes a chunk immediately, ignoring failures.
+    ///
+    /// This is used for control messages, such as heartbeat pings, whose order relative
+    /// to other messages does not matter.
+    fn write_detached(self: &Rc<Self>, chunk: &JsValue) {
+        self.start(chunk, false);
+    }
+
+    /// Hands a chunk to the writable stream and tracks its completion.
+    fn start(self: &Rc<Self>, chunk: &JsValue, report: bool) {
+        let promise = self.writer.write_with_chunk(chunk);
+        let _ = promise.then2(&self.on_written, if report { &self.on_failed } else { &self.on_written });
+        self.pending.set(self.pending.get() + 1);
+        // In-flight writes keep the writer alive, so that held back chunks are written.
+        self.keep_alive.replace(Some(self.clone()));
+    }
+
+    fn advance(self: &Rc<Self>) {
+        while !self.reading_blob.get() {
+            let chunk = {
+                let mut queue = self.queue.borrow_mut();
+                match queue.front() {
+                    None => break,
+                    Some(Chunk::Lent(_)) if self.pending.get() > 0 => break,
+                    Some(_) => queue.pop_front().unwrap(),
+                }
+            };
+
+            match chunk {
+                Chunk::Js(value) => self.start(&value, true),
+                Chunk::Blob(blob) => self.read_blob(blob),
+                Chunk::Lent(data) => {
+                    // SAFETY: since no write is pending, WebSocketStream copies the data
+                    //         synchronously, before Wasm memory can grow.
+                    let array = unsafe { uint8_array_for_api(&data) };
+                    self.start(&array, true);
                 }
             }
-            .boxed_local(),
-        )
+        }
+
+        self.check_drained();
     }
 
-    pub fn into_split(self) -> (Sender, Receiver) {
-        (self.sender, self.receiver)
+    fn read_blob(self: &Rc<Self>, blob: Blob) {
+        self.reading_blob.set(true);
+        self.keep_alive.replace(Some(self.clone()));
+
+        let this = self.clone();
+        spawn_local(async move {
+            match JsFuture::from(blob.array_buffer()).await {
+                Ok(buffer) => this.start(&buffer, true),
+                Err(err) => {
+                    this.failed.borrow_mut().get_or_insert(err);
+                }
+            }
+            this.reading_blob.set(false);
+            this.advance();
+        });
+    }
+
+    fn settled(self: &Rc<Self>) {
+        self.pending.set(self.pending.get() - 1);
+        self.advance();
+    }
+
+    fn is_drained(&self) -> bool {
+        self.pending.get() == 0 && !self.reading_blob.get() && self.queue.borrow().is_empty()
+    }
+
+    fn check_drained(&self) {
+        if self.is_drained() {
+            self.keep_alive.take();
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Polls until all chunks have been written.
+    ///
+    /// Returns the first error that occurred while writing.
+    fn poll_drained(&self, cx: &mut Context) -> Poll<Result<(), JsValue>> {
+        if !self.is_drained() {
+            self.waker.set(Some(cx.waker().clone()));
+            return Poll::Pending;
+        }
+
+        match self.failed.take() {
+            Some(err) => Poll::Ready(Err(err)),
+            None => Poll::Ready(Ok(())),
+        }
     }
 }
 
 pub struct Sender {
     socket: Rc<Guard>,
-    writer: WritableStreamDefaultWriter,
-    writing: Option<JsFuture>,
+    writer: Rc<Writer>,
     closing: Option<JsFuture>,
     buffered: usize,
     send_buffer_size: usize,
+    send_timer: Timer,
+    close_timer: Timer,
 }
 
 impl Sender {
-    fn new(socket: Rc<Guard>, writer: WritableStreamDefaultWriter, send_buffer_size: Option<usize>) -> Self {
+    fn new(
+        socket: Rc<Guard>, writer: Rc<Writer>, send_buffer_size: Option<usize>, send_timeout: Option<Duration>,
+        close_timeout: Option<Duration>,
+    ) -> Self {
         Self {
             socket,
             writer,
-            writing: None,
             closing: None,
             buffered: 0,
             send_buffer_size: send_buffer_size.unwrap_or(DEFAULT_SEND_BUFFER_SIZE),
+            send_timer: Timer::new(send_timeout),
+            close_timer: Timer::new(close_timeout),
         }
     }
 
+    pub(crate) fn guard(&self) -> &Rc<Guard> {
+        &self.socket
+    }
+
     #[track_caller]
     pub fn close(self, code: u16, reason: &str) {
-        let options = Object::new();
-        Reflect::set(&options, &JsValue::from("closeCode"), &JsValue::from(code)).unwrap();
-        Reflect::set(&options, &JsValue::from("reason"), &JsValue::from_str(reason)).unwrap();
-        self.socket.close(&options).unwrap();
-        self.socket.closed.set(true);
+        self.socket.close_with_reason(code, reason);
+    }
+
+    /// Starts sending an owned binary message.
+    ///
+    /// The message is written as a view into Wasm memory instead of copying it
+    /// into a JavaScript buffer first.
+    pub fn start_send_payload(&mut self, data: Payload) -> Result<(), WebSocketError> {
+        let len = data.len();
+        self.enqueue(Chunk::Lent(data), len);
+        Ok(())
+    }
+
+    fn enqueue(&mut self, chunk: Chunk, len: usize) {
+        if self.buffered >= self.send_buffer_size {
+            panic!("WebSocket not ready for sending");
+        }
+
+        self.writer.write(chunk);
+        self.buffered += len;
+
+        if self.buffered >= self.send_buffer_size {
+            self.send_timer.start();
+        }
     }
 }
 
 impl Sink<(&JsValue, usize)> for Sender {
-    type Error = io::Error;
+    type Error = WebSocketError;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        let Some(writing) = &mut self.writing else {
+        if self.buffered < self.send_buffer_size {
             return Poll::Ready(Ok(()));
-        };
+        }
 
-        let res = match ready!(writing.poll_unpin(cx)) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(js_err(ErrorKind::ConnectionReset, &err)),
+        let res = match self.writer.poll_drained(cx) {
+            Poll::Ready(Ok(())) => Ok(()),
+            Poll::Ready(Err(err)) => Err(self.socket.failure().unwrap_or_else(|| WebSocketError::js(&err))),
+            Poll::Pending => {
+                ready!(self.send_timer.poll_expired(cx));
+                Err(self.socket.fail(WebSocketError::TimedOut))
+            }
         };
 
-        self.writing = None;
         self.buffered = 0;
+        self.send_timer.stop();
 
         Poll::Ready(res)
     }
 
     fn start_send(mut self: Pin<&mut Self>, (item, len): (&JsValue, usize)) -> Result<(), Self::Error> {
-        if self.writing.is_some() {
-            panic!("WebSocket not ready for sending");
-        }
-
-        let promise = self.writer.write_with_chunk(item);
-        self.buffered += len;
-
-        if self.buffered >= self.send_buffer_size {
-            self.writing = Some(JsFuture::from(promise));
-        }
-
+        let chunk = match item.dyn_ref::<Blob>() {
+            Some(blob) => Chunk::Blob(blob.clone()),
+            None => Chunk::Js(item.clone()),
+        };
+        self.enqueue(chunk, len);
         Ok(())
     }
 
@@ -243,16 +652,29 @@ impl Sink<(&JsValue, usize)> for Sender {
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         if self.closing.is_none() {
-            self.closing = Some(JsFuture::from(self.writer.close()));
+            // Held back chunks must be written before closing the writable stream.
+            let writer = self.writer.clone();
+            let promise = future_to_promise(async move {
+                let _ = poll_fn(|cx| writer.poll_drained(cx)).await;
+                JsFuture::from(writer.writer.close()).await
+            });
+            self.closing = Some(JsFuture::from(promise));
+            self.socket.closing();
+            self.close_timer.start();
         }
 
         let Some(closing) = &mut self.closing else { unreachable!() };
-        let res = match ready!(closing.poll_unpin(cx)) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(js_err(ErrorKind::ConnectionReset, &err)),
+        let res = match closing.poll_unpin(cx) {
+            Poll::Ready(Ok(_)) => Ok(()),
+            Poll::Ready(Err(err)) => Err(WebSocketError::js(&err)),
+            Poll::Pending => {
+                ready!(self.close_timer.poll_expired(cx));
+                Err(WebSocketError::TimedOut)
+            }
         };
 
         self.closing = None;
+        self.close_timer.stop();
         Poll::Ready(res)
     }
 }
@@ -264,50 +686,224 @@ impl Drop for Sender {
 }
 
 pub struct Receiver {
-    _socket: Rc<Guard>,
+    socket: Rc<Guard>,
     reader: ReadableStreamDefaultReader,
     reading: Option<JsFuture>,
+    closing: Option<Closed>,
+    idle: Timer,
+    heartbeat: Option<Rc<heartbeat::Monitor>>,
+    limiter: ReceiveLimiter,
+    credits: Option<(Rc<Credits>, Rc<Writer>)>,
+    prefetch: bool,
+    mode: ReceiveMode,
+    pause: Pause,
+    peeked: Option<Received>,
 }
 
 impl Receiver {
-    fn new(socket: Rc<Guard>, reader: ReadableStreamDefaultReader) -> Self {
-        Self { _socket: socket, reader, reading: None }
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        socket: Rc<Guard>, reader: ReadableStreamDefaultReader, receive_timeout: Option<Duration>,
+        heartbeat: Option<Rc<heartbeat::Monitor>>, limiter: ReceiveLimiter,
+        credits: Option<(Rc<Credits>, Rc<Writer>)>, prefetch: bool, mode: ReceiveMode,
+    ) -> Self {
+        let mut idle = Timer::new(receive_timeout);
+        idle.start();
+        Self {
+            socket,
+            reader,
+            reading: None,
+            closing: None,
+            idle,
+            heartbeat,
+            limiter,
+            credits,
+            prefetch,
+            mode,
+            pause: Pause::default(),
+            peeked: None,
+        }
     }
-}
 
-impl Stream for Receiver {
-    type Item = io::Result<Msg>;
+    /// Stops reading from the browser until resumed.
+    pub fn pause(&mut self) {
+        self.pause.pause();
+    }
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        if self.reading.is_none() {
-            self.reading = Some(JsFuture::from(self.reader.read()));
+    /// Resumes reading from the browser.
+    pub fn resume(&mut self) {
+        if self.pause.is_paused() {
+            self.idle.start();
         }
+        self.pause.resume();
+    }
 
-        let Some(reading) = &mut self.reading else { unreachable!() };
-        let res = match ready!(reading.poll_unpin(cx)) {
-            Ok(data) => {
-                let result: ReadableStreamReadResult = data.unchecked_into();
-                if result.get_done().unwrap_or_default() {
-                    self.reading = None;
-                    None
-                } else {
-                    let chunk = result.get_value();
-                    // Pipeline: start next read immediately to reduce per-message latency.
-                    self.reading = Some(JsFuture::from(self.reader.read()));
-                    if chunk.is_string() {
-                        Some(Ok(Msg::Text(chunk.as_string().unwrap())))
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Receives a message if one is available without waiting.
+    ///
+    /// Fails with [WebSocketError::Closed] once the WebSocket has been closed
+    /// and all messages have been received.
+    pub fn try_recv(&mut self) -> Result<Option<Msg>, WebSocketError> {
+        Ok(self.try_recv_received()?.map(Received::into_msg))
+    }
+
+    fn try_recv_received(&mut self) -> Result<Option<Received>, WebSocketError> {
+        match self.poll_recv(&mut Context::from_waker(noop_waker_ref())) {
+            Poll::Ready(Some(res)) => res.map(Some),
+            Poll::Ready(None) => Err(self.socket.closed_err()),
+            Poll::Pending => Ok(None),
+        }
+    }
+
+    /// Returns the next message if one is available without waiting, but does not remove it.
+    pub fn peek(&mut self) -> Result<Option<&Msg>, WebSocketError> {
+        if self.peeked.is_none() {
+            self.peeked = self.try_recv_received()?;
+        }
+        Ok(self.peeked.as_mut().and_then(Received::as_msg))
+    }
+
+    /// Returns a received message, so that it is returned by the next receive.
+    pub(crate) fn unrecv(&mut self, msg: Received) {
+        debug_assert!(self.peeked.is_none());
+        self.peeked = Some(msg);
+    }
+
+    /// Number of messages that have been read from the browser, but not yet received.
+    ///
+    /// This is at most one, namely a completed prefetched read.
+    pub fn queued_len(&mut self) -> usize {
+        usize::from(self.fill_peeked())
+    }
+
+    /// Size in bytes of the messages that have been read from the browser, but not yet received.
+    pub fn queued_bytes(&mut self) -> usize {
+        match self.fill_peeked() {
+            true => self.peeked.as_ref().map_or(0, Received::len),
+            false => 0,
+        }
+    }
+
+    /// Takes a message from a completed read, if available, and returns whether one is peeked.
+    fn fill_peeked(&mut self) -> bool {
+        if self.peeked.is_none() {
+            self.peeked = self.try_recv_received().ok().flatten();
+        }
+        self.peeked.is_some()
+    }
+
+    pub(crate) fn guard(&self) -> &Rc<Guard> {
+        &self.socket
+    }
+
+    fn set_receiving(&self, receiving: bool) {
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.set_receiving(receiving);
+        }
+    }
+
+    /// Polls for the next received message, which is never a blob.
+    pub(crate) fn poll_recv_loaded(
+        &mut self, cx: &mut Context,
+    ) -> Poll<Option<Result<Received, WebSocketError>>> {
+        self.poll_recv(cx)
+    }
+
+    /// Polls for the next received message without copying binary data into Wasm memory.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context) -> Poll<Option<Result<Received, WebSocketError>>> {
+        ready!(self.pause.poll_resumed(cx));
+
+        if let Some(msg) = self.peeked.take() {
+            return Poll::Ready(Some(Ok(msg)));
+        }
+
+        loop {
+            if let Some(closing) = &mut self.closing {
+                let reason = ready!(closing.poll_unpin(cx));
+                self.closing = None;
+                return match reason.was_clean {
+                    true => Poll::Ready(None),
+                    false => Poll::Ready(Some(Err(WebSocketError::Closed(reason)))),
+                };
+            }
+
+            if self.reading.is_none() {
+                self.reading = Some(JsFuture::from(self.reader.read()));
+            }
+
+            let Some(reading) = &mut self.reading else { unreachable!() };
+            let res = match reading.poll_unpin(cx) {
+                Poll::Ready(res) => res,
+                Poll::Pending => {
+                    self.set_receiving(true);
+                    ready!(self.idle.poll_expired(cx));
+                    return Poll::Ready(Some(Err(self.socket.fail(WebSocketError::TimedOut))));
+                }
+            };
+            self.set_receiving(false);
+
+            let res = match res {
+                Ok(data) => {
+                    let result: ReadableStreamReadResult = data.unchecked_into();
+                    if result.get_done().unwrap_or_default() {
+                        self.reading = None;
+                        match self.socket.failure() {
+                            Some(err) => Some(Err(err)),
+                            None => {
+                                self.closing = Some(self.socket.wait_closed());
+                                continue;
+                            }
+                        }
                     } else {
-                        Some(Ok(Msg::Binary(Uint8Array::new(&chunk).to_vec())))
+                        self.idle.start();
+                        let chunk = result.get_value();
+                        // Pipeline: start next read immediately to reduce per-message latency.
+                        self.reading = self.prefetch.then(|| JsFuture::from(self.reader.read()));
+                        let mut msg = Received::from_js(&chunk, self.mode);
+                        if let Some((credits, writer)) = &self.credits {
+                            if let Some(grant) = credits.consumed(msg.len()) {
+                                writer.write_detached(&JsValue::from_str(&grant));
+                            }
+                        }
+                        if let Err(reason) = self.limiter.check(msg.len()) {
+                            self.socket.terminate(reason.clone(), WebSocketError::Closed(reason.clone()));
+                            return Poll::Ready(Some(Err(WebSocketError::Closed(reason))));
+                        }
+                        if self
+                            .heartbeat
+                            .as_ref()
+                            .is_some_and(|heartbeat| msg.as_msg().is_some_and(|msg| heartbeat.is_pong(msg)))
+                        {
+                            continue;
+                        }
+                        Some(Ok(msg))
                     }
                 }
-            }
-            Err(err) => {
-                self.reading = None;
-                Some(Err(js_err(ErrorKind::ConnectionReset, &err)))
-            }
-        };
+                Err(_) => {
+                    self.reading = None;
+                    match self.socket.failure() {
+                        Some(err) => Some(Err(err)),
+                        None => {
+                            self.closing = Some(self.socket.wait_closed());
+                            continue;
+                        }
+                    }
+                }
+            };
 
-        Poll::Ready(res)
+            return Poll::Ready(res);
+        }
+    }
+}
+
+impl Stream for Receiver {
+    type Item = Result<Msg, WebSocketError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.poll_recv(cx).map_ok(Received::into_msg)
     }
 }
 
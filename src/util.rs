@@ -1,13 +1,22 @@
 //! Utils.
 
+use futures_core::Stream;
+use futures_util::{
+    future::{select, Either},
+    FutureExt, StreamExt,
+};
 use js_sys::{global, Promise, Reflect};
 use std::{
-    io::{self, ErrorKind},
+    future::Future,
+    pin::{pin, Pin},
+    task::{ready, Context, Poll, Waker},
     time::Duration,
 };
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{Window, WorkerGlobalScope};
+use web_sys::{AbortSignal, AddEventListenerOptions, Performance, Window, WorkerGlobalScope};
+
+use crate::{Msg, WebSocketError};
 
 /// Sleep for the specified duration.
 pub async fn sleep(duration: Duration) {
@@ -25,6 +34,208 @@ pub async fn sleep(duration: Duration) {
     JsFuture::from(promise).await.unwrap();
 }
 
+thread_local! {
+    static PERFORMANCE: Performance = {
+        let global = global();
+        if let Some(window) = global.dyn_ref::<Window>() {
+            window.performance().expect("performance unavailable")
+        } else if let Some(worker) = global.dyn_ref::<WorkerGlobalScope>() {
+            worker.performance().expect("performance unavailable")
+        } else {
+            panic!("unsupported global scope");
+        }
+    };
+}
+
+/// Current monotonic time in milliseconds.
+///
+/// Unlike the wall-clock time, it is not affected by system clock adjustments.
+pub fn now() -> f64 {
+    PERFORMANCE.with(|performance| performance.now())
+}
+
+/// Converts a time obtained from [now] into milliseconds since the Unix epoch.
+pub fn unix_time(time: f64) -> f64 {
+    PERFORMANCE.with(|performance| performance.time_origin() + time)
+}
+
+/// Awaits the future, giving up after the specified duration.
+///
+/// Returns `None` if the timeout elapsed.
+pub async fn timeout<F: Future>(duration: Duration, fut: F) -> Option<F::Output> {
+    match select(pin!(fut), pin!(sleep(duration))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(((), _)) => None,
+    }
+}
+
+/// A timer for use in poll functions that can be restarted cheaply.
+pub struct Timer {
+    timeout: Option<Duration>,
+    deadline: Option<f64>,
+    sleep: Option<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+impl Timer {
+    /// Creates a new, stopped timer.
+    ///
+    /// If `timeout` is `None`, the timer never expires.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout, deadline: None, sleep: None }
+    }
+
+    /// (Re-)starts the timer.
+    pub fn start(&mut self) {
+        if let Some(timeout) = self.timeout {
+            self.deadline = Some(now() + timeout.as_secs_f64() * 1000.);
+        }
+    }
+
+    /// Stops the timer.
+    pub fn stop(&mut self) {
+        self.deadline = None;
+        self.sleep = None;
+    }
+
+    /// Polls whether the timer has expired.
+    ///
+    /// A stopped timer never expires. Once expired, the timer is stopped.
+    pub fn poll_expired(&mut self, cx: &mut Context) -> Poll<()> {
+        loop {
+            let Some(deadline) = self.deadline else { return Poll::Pending };
+
+            let remaining = deadline - now();
+            if remaining <= 0. {
+                self.stop();
+                return Poll::Ready(());
+            }
+
+            // The deadline may have been moved since the sleep was started,
+            // thus it is checked again once the sleep has finished.
+            let sleep =
+                self.sleep.get_or_insert_with(|| sleep(Duration::from_secs_f64(remaining / 1000.)).boxed_local());
+            ready!(sleep.poll_unpin(cx));
+            self.sleep = None;
+        }
+    }
+}
+
+/// Pause state of a receiver.
+#[derive(Default)]
+pub struct Pause {
+    paused: bool,
+    waker: Option<Waker>,
+}
+
+impl Pause {
+    /// Pauses.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes and wakes the task that polled while paused.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns pending while paused.
+    pub fn poll_resumed(&mut self, cx: &mut Context) -> Poll<()> {
+        if self.paused {
+            self.waker = Some(cx.waker().clone());
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+/// Receives at least one and up to `limit` messages from the stream into `buf`,
+/// taking all messages that are available without waiting.
+///
+/// Returns zero if the stream has ended or `limit` is zero.
+pub fn poll_recv_many<S>(
+    stream: &mut S, cx: &mut Context, buf: &mut Vec<Msg>, limit: usize,
+) -> Poll<Result<usize, WebSocketError>>
+where
+    S: Stream<Item = Result<Msg, WebSocketError>> + Unpin,
+{
+    let mut n = 0;
+    while n < limit {
+        match stream.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(msg))) => {
+                buf.push(msg);
+                n += 1;
+            }
+            // Errors are persistent and thus reported by the next receive.
+            Poll::Ready(Some(Err(_)) | None) | Poll::Pending if n > 0 => break,
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Err(err)),
+            Poll::Ready(None) => break,
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+    Poll::Ready(Ok(n))
+}
+
+/// A listener for the abort event of an [AbortSignal].
+///
+/// The listener is removed when this is dropped.
+pub struct AbortListener {
+    signal: AbortSignal,
+    callback: Closure<dyn FnMut()>,
+}
+
+impl Drop for AbortListener {
+    fn drop(&mut self) {
+        let _ = self.signal.remove_event_listener_with_callback("abort", self.callback.as_ref().unchecked_ref());
+    }
+}
+
+/// Calls the function once the signal is aborted, unless the returned listener has been dropped before.
+pub fn on_abort(signal: &AbortSignal, f: impl FnOnce() + 'static) -> AbortListener {
+    let mut f = Some(f);
+    let callback = Closure::<dyn FnMut()>::new(move || {
+        if let Some(f) = f.take() {
+            f();
+        }
+    });
+
+    let options = AddEventListenerOptions::new();
+    options.set_once(true);
+    signal
+        .add_event_listener_with_callback_and_add_event_listener_options(
+            "abort",
+            callback.as_ref().unchecked_ref(),
+            &options,
+        )
+        .unwrap();
+
+    AbortListener { signal: signal.clone(), callback }
+}
+
+/// Resolves once the signal is aborted.
+pub async fn aborted(signal: &AbortSignal) {
+    if signal.aborted() {
+        return;
+    }
+
+    let mut listener = None;
+    let promise = Promise::new(&mut |resolve, _reject| {
+        listener = Some(on_abort(signal, move || {
+            let _ = resolve.call0(&JsValue::undefined());
+        }));
+    });
+    let _listener = listener;
+    let _ = JsFuture::from(promise).await;
+}
+
 /// Extracts the error message from a JavaScript error.
 pub fn js_err_msg(value: &JsValue) -> Option<String> {
     if let Some(js_err) = value.dyn_ref::<js_sys::Error>() {
@@ -48,12 +259,6 @@ pub fn js_err_msg(value: &JsValue) -> Option<String> {
     None
 }
 
-/// Creates an IO error from the JavaScript error.
-pub fn js_err(kind: ErrorKind, value: &JsValue) -> io::Error {
-    let msg = js_err_msg(value).unwrap_or_else(|| kind.to_string());
-    io::Error::new(kind, msg)
-}
-
 /// Creates a [`Uint8Array`] suitable for passing to Web APIs.
 ///
 /// When Wasm runs with threads, linear memory is backed by [`SharedArrayBuffer`],
@@ -2,11 +2,13 @@
 
 use futures::{future, SinkExt, StreamExt, TryStreamExt};
 use log::{info, warn};
+use std::time::Duration;
 use tokio::{
+    io::AsyncReadExt,
     net::{TcpListener, TcpStream},
-    sync::{oneshot, oneshot::error::TryRecvError},
+    sync::{mpsc, oneshot, oneshot::error::TryRecvError},
     task::JoinSet,
-    time::Instant,
+    time::{sleep, Instant},
     try_join,
 };
 use tokio_tungstenite::tungstenite::{
@@ -20,8 +22,10 @@ async fn main() {
 
     let echo_server = tokio::spawn(echo_server());
     let speed_server = tokio::spawn(speed_server());
+    let unresponsive_server = tokio::spawn(unresponsive_server());
+    let silent_server = tokio::spawn(silent_server());
 
-    try_join!(echo_server, speed_server).unwrap();
+    try_join!(echo_server, speed_server, unresponsive_server, silent_server).unwrap();
 }
 
 async fn echo_server() {
@@ -47,6 +51,7 @@ async fn accept_echo(stream: TcpStream) {
     info!("New WebSocket echo connection: {}", addr);
 
     let (mut write, mut read) = ws_stream.split();
+    let mut muted = false;
 
     while let Some(msg) = read.next().await {
         let msg = match msg {
@@ -57,15 +62,29 @@ async fn accept_echo(stream: TcpStream) {
             }
         };
 
+        if muted {
+            continue;
+        }
+
         let res = match msg {
             Message::Text(utf8_bytes) => {
-                if utf8_bytes.to_string().starts_with("CLOSE") {
+                if utf8_bytes.to_string().starts_with("MUTE") {
+                    muted = true;
+                    continue;
+                } else if utf8_bytes.to_string().starts_with("DROP") {
+                    info!("Dropping connection {addr} without close handshake");
+                    return;
+                } else if utf8_bytes.to_string().starts_with("CLOSE") {
                     write
                         .send(Message::Close(Some(CloseFrame {
                             code: CloseCode::Library(3999),
                             reason: utf8_bytes,
                         })))
                         .await
+                } else if utf8_bytes.to_string().starts_with("RESTART") {
+                    write
+                        .send(Message::Close(Some(CloseFrame { code: CloseCode::Restart, reason: utf8_bytes })))
+                        .await
                 } else {
                     write.send(Message::Text(utf8_bytes)).await
                 }
@@ -85,6 +104,39 @@ async fn accept_echo(stream: TcpStream) {
         .expect("Failed to forward messages")
 }
 
+/// Accepts connections but never completes the WebSocket handshake.
+async fn unresponsive_server() {
+    let addr = "0.0.0.0:8767";
+    let try_socket = TcpListener::bind(addr).await;
+    let listener = try_socket.expect("Failed to bind");
+    info!("Unresponsive listening on: {}", addr);
+
+    while let Ok((mut stream, addr)) = listener.accept().await {
+        info!("New unresponsive connection: {}", addr);
+        tokio::spawn(async move {
+            let mut buf = [0; 1024];
+            while stream.read(&mut buf).await.is_ok_and(|n| n > 0) {}
+        });
+    }
+}
+
+/// Completes the WebSocket handshake but then neither reads nor answers the close handshake.
+async fn silent_server() {
+    let addr = "0.0.0.0:8768";
+    let try_socket = TcpListener::bind(addr).await;
+    let listener = try_socket.expect("Failed to bind");
+    info!("Silent listening on: {}", addr);
+
+    while let Ok((stream, addr)) = listener.accept().await {
+        tokio::spawn(async move {
+            let Ok(ws_stream) = tokio_tungstenite::accept_async(stream).await else { return };
+            info!("New silent connection: {}", addr);
+            sleep(Duration::from_secs(30)).await;
+            drop(ws_stream);
+        });
+    }
+}
+
 async fn speed_server() {
     let addr = "0.0.0.0:8766";
     let try_socket = TcpListener::bind(addr).await;
@@ -108,23 +160,47 @@ async fn accept_speed(stream: TcpStream) {
     let mut js = JoinSet::new();
     let (mut write, mut read) = ws_stream.split();
 
-    let Some(Ok(Message::Text(mode))) = read.next().await else { return };
+    // Flow control credits may be granted before the mode is sent.
+    let (mut credit_bytes, mut credit_msgs) = (0, 0);
+    let mode = loop {
+        let Some(Ok(Message::Text(text))) = read.next().await else { return };
+        match parse_credit(&text) {
+            Some((bytes, msgs)) => {
+                credit_bytes += bytes;
+                credit_msgs += msgs;
+            }
+            None => break text,
+        }
+    };
     info!("Speed test mode is {mode}");
 
     let (closed_tx, mut closed_rx) = oneshot::channel();
+    let (credit_tx, mut credit_rx) = mpsc::unbounded_channel();
+    let flow_control = mode == "recv-credit";
 
     // Sender
-    if mode == "recv" || mode == "both" {
+    if mode == "recv" || mode == "both" || flow_control {
         js.spawn(async move {
             let start = Instant::now();
             let mut total = 0;
 
             let msg = Bytes::from(vec![1; MSG_SIZE]);
-            while closed_rx.try_recv() == Err(TryRecvError::Empty) {
+            'send: while closed_rx.try_recv() == Err(TryRecvError::Empty) {
+                while flow_control && (credit_bytes <= 0 || credit_msgs <= 0) {
+                    if write.flush().await.is_err() {
+                        break 'send;
+                    }
+                    let Some((bytes, msgs)) = credit_rx.recv().await else { break 'send };
+                    credit_bytes += bytes;
+                    credit_msgs += msgs;
+                }
+
                 if write.feed(Message::Binary(msg.clone())).await.is_err() {
                     break;
                 }
                 total += msg.len();
+                credit_bytes -= msg.len() as i64;
+                credit_msgs -= 1;
             }
 
             let mb = total as f64 / MB as f64;
@@ -156,8 +232,14 @@ async fn accept_speed(stream: TcpStream) {
     } else {
         js.spawn(async move {
             while let Some(Ok(msg)) = read.next().await {
-                if let Message::Close(_) = msg {
-                    break;
+                match msg {
+                    Message::Text(text) => {
+                        if let Some(credit) = parse_credit(&text) {
+                            let _ = credit_tx.send(credit);
+                        }
+                    }
+                    Message::Close(_) => break,
+                    _ => (),
                 }
             }
 
@@ -167,3 +249,9 @@ async fn accept_speed(stream: TcpStream) {
 
     js.join_all().await;
 }
+
+/// Parses a flow control message granting credits in bytes and messages.
+fn parse_credit(text: &str) -> Option<(i64, i64)> {
+    let (bytes, msgs) = text.strip_prefix("FLOW-CREDIT ")?.split_once(' ')?;
+    Some((bytes.parse().ok()?, msgs.parse().ok()?))
+}
@@ -128,6 +128,50 @@ async fn recv_small(interface: Interface) {
     socket.close_with_reason(CloseCode::NormalClosure, "done");
 }
 
+/// Benchmark: receive small messages from echo using `recv_many`.
+async fn recv_many_small(interface: Interface) {
+    const BATCH: usize = 1000;
+    const DURATION: f64 = 5.;
+
+    static SEMAPHORE: Semaphore = Semaphore::const_new(1);
+    let _permit = SEMAPHORE.acquire().await.unwrap_log();
+
+    let url = echo_url();
+    let mut builder = WebSocketBuilder::new(&url);
+    builder.set_interface(interface);
+
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("recv_many_small: connected via {interface:?}");
+
+    let msg = "x";
+    let mut buf = Vec::with_capacity(BATCH);
+    let start = now();
+    let mut count: u64 = 0;
+    let mut calls: u64 = 0;
+
+    while now() - start < DURATION {
+        // Send a batch.
+        for _ in 0..BATCH {
+            socket.send(msg).await.unwrap_log();
+        }
+        // Receive the batch.
+        let mut received = 0;
+        while received < BATCH {
+            buf.clear();
+            received += socket.recv_many(&mut buf, BATCH - received).await.unwrap_log();
+            calls += 1;
+        }
+        count += BATCH as u64;
+    }
+
+    let elapsed = now() - start;
+    let msg_per_sec = count as f64 / elapsed;
+    let per_call = count as f64 / calls as f64;
+    msg!("recv_many_small {interface:?}: {count} msgs in {elapsed:.1}s => {msg_per_sec:.0} msg/s, {per_call:.1} msgs/call");
+
+    socket.close_with_reason(CloseCode::NormalClosure, "done");
+}
+
 macro_rules! require_stream_support {
     () => {
         if !Interface::Stream.is_supported() {
@@ -175,3 +219,16 @@ async fn recv_small_stream() {
 async fn recv_small_standard() {
     recv_small(Interface::Standard).await;
 }
+
+// --- Receive benchmarks (batched echo using recv_many) ---
+
+#[wasm_bindgen_test]
+async fn recv_many_small_stream() {
+    require_stream_support!();
+    recv_many_small(Interface::Stream).await;
+}
+
+#[wasm_bindgen_test]
+async fn recv_many_small_standard() {
+    recv_many_small(Interface::Standard).await;
+}
@@ -17,15 +17,18 @@ fn url() -> String {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Mode {
     Send,
+    SendCopy,
     Recv,
+    RecvCredit,
     Both,
 }
 
 impl AsRef<str> for Mode {
     fn as_ref(&self) -> &'static str {
         match self {
-            Mode::Send => "send",
+            Mode::Send | Mode::SendCopy => "send",
             Mode::Recv => "recv",
+            Mode::RecvCredit => "recv-credit",
             Mode::Both => "both",
         }
     }
@@ -44,6 +47,10 @@ async fn speed(interface: Option<Interface>, mode: Mode) {
     if let Some(interface) = interface {
         builder.set_interface(interface);
     }
+    if mode == Mode::RecvCredit {
+        builder.set_flow_control(FlowControl::new(MB, 256));
+        builder.set_receive_buffer_size(MB + MSG_SIZE);
+    }
 
     log!("Connecting to {url} using {interface:?}");
     let mut socket = builder.connect().await.expect_log("connect failed");
@@ -57,7 +64,7 @@ async fn speed(interface: Option<Interface>, mode: Mode) {
 
     // Sender
     let (send_done_tx, send_done_rx) = oneshot::channel();
-    if mode == Mode::Send || mode == Mode::Both {
+    if mode == Mode::Send || mode == Mode::SendCopy || mode == Mode::Both {
         spawn_local(async move {
             let start = now();
             let mut total = 0;
@@ -65,7 +72,12 @@ async fn speed(interface: Option<Interface>, mode: Mode) {
             while now() - start < DURATION {
                 let data = vec![2; MSG_SIZE];
                 total += data.len();
-                write.send(data).await.unwrap_log();
+                if mode == Mode::SendCopy {
+                    // Sending a slice copies the data, whereas an owned vector is lent to JavaScript.
+                    write.send(data.as_slice()).await.unwrap_log();
+                } else {
+                    write.send(data).await.unwrap_log();
+                }
             }
 
             let mb = total as f64 / MB as f64;
@@ -83,7 +95,7 @@ async fn speed(interface: Option<Interface>, mode: Mode) {
 
     // Receiver
     let (recv_done_tx, recv_done_rx) = oneshot::channel();
-    if mode == Mode::Recv || mode == Mode::Both {
+    if mode == Mode::Recv || mode == Mode::RecvCredit || mode == Mode::Both {
         spawn_local(async move {
             let start = now();
             let mut total = 0;
@@ -126,6 +138,12 @@ async fn send_stream() {
     speed(Some(Interface::Stream), Mode::Send).await;
 }
 
+#[wasm_bindgen_test]
+async fn send_copy_stream() {
+    require_stream_support!();
+    speed(Some(Interface::Stream), Mode::SendCopy).await;
+}
+
 #[wasm_bindgen_test]
 async fn recv_stream() {
     require_stream_support!();
@@ -143,7 +161,23 @@ async fn send_standard() {
     speed(Some(Interface::Standard), Mode::Send).await;
 }
 
+#[wasm_bindgen_test]
+async fn send_copy_standard() {
+    speed(Some(Interface::Standard), Mode::SendCopy).await;
+}
+
 #[wasm_bindgen_test]
 async fn recv_standard() {
     speed(Some(Interface::Standard), Mode::Recv).await;
 }
+
+#[wasm_bindgen_test]
+async fn recv_credit_stream() {
+    require_stream_support!();
+    speed(Some(Interface::Stream), Mode::RecvCredit).await;
+}
+
+#[wasm_bindgen_test]
+async fn recv_credit_standard() {
+    speed(Some(Interface::Standard), Mode::RecvCredit).await;
+}
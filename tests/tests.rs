@@ -1,4 +1,8 @@
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{
+    future::{select, Either},
+    FutureExt, SinkExt, StreamExt,
+};
+use std::{io, io::ErrorKind, pin::pin, time::Duration};
 use tokio::{
     io::{duplex, AsyncReadExt, AsyncWriteExt},
     sync::{mpsc, oneshot},
@@ -10,7 +14,7 @@ use wasm_bindgen_test::wasm_bindgen_test;
 use websocket_web::*;
 
 mod util;
-use util::ResultExt;
+use util::{sleep, ResultExt};
 
 fn url() -> String {
     let host = web_sys::window().unwrap().location().hostname().unwrap();
@@ -236,3 +240,1553 @@ async fn io_stream() {
 async fn io_standard() {
     io(Some(Interface::Standard)).await;
 }
+
+async fn reconnect(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let backoff = Backoff { initial_delay: Duration::from_millis(100), ..Default::default() };
+    let mut socket =
+        ReconnectingWebSocket::connect_with_backoff(builder, backoff).await.expect_log("connect failed");
+    let mut events = socket.events();
+    log!("Connected: {socket:?}");
+
+    socket.send(Msg::Text("before".to_string())).await.unwrap_log();
+    let recved = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(recved, Msg::Text("before".to_string()));
+
+    log!("Requesting server restart");
+    socket.send(Msg::Text("RESTART".to_string())).await.unwrap_log();
+
+    let reconnected = async {
+        loop {
+            let event = events.next().await.expect_log("events ended");
+            log!("Event: {event:?}");
+            match event {
+                ReconnectEvent::Disconnected { reason, error } => {
+                    assert_eq!(reason.code, CloseCode::ServiceRestart);
+                    assert!(error.is_none(), "unexpected error: {error:?}");
+                }
+                ReconnectEvent::Reconnected { .. } => break,
+                ReconnectEvent::Stopped => panic_log!("reconnecting stopped"),
+                _ => (),
+            }
+        }
+    };
+    match select(socket.next(), pin!(reconnected)).await {
+        Either::Left((msg, _)) => panic_log!("unexpected message: {msg:?}"),
+        Either::Right(((), _)) => (),
+    }
+    assert!(socket.is_connected());
+
+    socket.send(Msg::Text("after".to_string())).await.unwrap_log();
+    let recved = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(recved, Msg::Text("after".to_string()));
+
+    socket.close_with_reason(CloseCode::NormalClosure, "goodbye");
+}
+
+#[wasm_bindgen_test]
+async fn reconnect_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    reconnect(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn reconnect_standard() {
+    reconnect(Some(Interface::Standard)).await;
+}
+
+async fn reconnect_error(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_receive_timeout(Duration::from_millis(300));
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = ReconnectingWebSocket::connect(builder).await.expect_log("connect failed");
+    socket.set_reconnect_filter(|_| false);
+    let mut events = socket.events();
+    log!("Connected: {socket:?}");
+
+    log!("Waiting for receive timeout");
+    match socket.next().await {
+        Some(Err(WebSocketError::TimedOut)) => (),
+        other => panic_log!("expected receive timeout, but got {other:?}"),
+    }
+    assert!(socket.next().await.is_none());
+
+    match events.next().await {
+        Some(ReconnectEvent::Disconnected { error: Some(WebSocketError::TimedOut), .. }) => (),
+        other => panic_log!("expected disconnect because of timeout, but got {other:?}"),
+    }
+    assert!(matches!(events.next().await, Some(ReconnectEvent::Stopped)));
+
+    match socket.flush().await {
+        Err(WebSocketError::Closed(reason)) => {
+            log!("Flush failed: {reason}");
+        }
+        other => panic_log!("expected flush to fail, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn reconnect_error_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    reconnect_error(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn reconnect_error_standard() {
+    reconnect_error(Some(Interface::Standard)).await;
+}
+
+async fn receive_timeout(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_receive_timeout(Duration::from_millis(500));
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    socket.send("hi").await.unwrap_log();
+    let recved = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(recved, Msg::Text("hi".to_string()));
+
+    log!("Waiting for receive timeout");
+    match socket.next().await {
+        Some(Err(err @ WebSocketError::TimedOut)) => {
+            log!("Receive timed out: {err}");
+        }
+        other => panic_log!("expected receive timeout, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn receive_timeout_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    receive_timeout(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn receive_timeout_standard() {
+    receive_timeout(Some(Interface::Standard)).await;
+}
+
+async fn connect_timeout(interface: Option<Interface>) {
+    let host = web_sys::window().unwrap().location().hostname().unwrap();
+    let url = format!("ws://{host}:8767");
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_connect_timeout(Duration::from_millis(500));
+
+    log!("Connecting to unresponsive {url} using {interface:?}");
+    match builder.connect().await {
+        Err(err @ WebSocketError::TimedOut) => {
+            log!("Connect timed out: {err}");
+        }
+        other => panic_log!("expected connect timeout, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn connect_timeout_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    connect_timeout(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn connect_timeout_standard() {
+    connect_timeout(Some(Interface::Standard)).await;
+}
+
+async fn send_timeout(interface: Option<Interface>) {
+    let host = web_sys::window().unwrap().location().hostname().unwrap();
+    let url = format!("ws://{host}:8768");
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_send_buffer_size(1_048_576);
+    builder.set_send_timeout(Duration::from_millis(500));
+
+    log!("Connecting to non-reading {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    log!("Filling send buffer");
+    for _ in 0..1024 {
+        match socket.send(vec![0; 1_048_576]).await {
+            Ok(()) => (),
+            Err(err @ WebSocketError::TimedOut) => {
+                log!("Send timed out: {err}");
+                return;
+            }
+            Err(err) => panic_log!("expected send timeout, but got {err:?}"),
+        }
+    }
+    panic_log!("expected send timeout, but all data was sent");
+}
+
+#[wasm_bindgen_test]
+async fn send_timeout_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    send_timeout(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn send_timeout_standard() {
+    send_timeout(Some(Interface::Standard)).await;
+}
+
+async fn close_timeout(interface: Option<Interface>) {
+    let host = web_sys::window().unwrap().location().hostname().unwrap();
+    let url = format!("ws://{host}:8768");
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_close_timeout(Duration::from_millis(500));
+
+    log!("Connecting to non-reading {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let closed = socket.closed();
+    let handle = socket.close_handle();
+    log!("Closing");
+    assert!(handle.close());
+
+    let reason = closed.await;
+    log!("Closed: {reason:?}");
+    assert_eq!(reason.code, CloseCode::AbnormalClosure);
+    assert_eq!(reason.reason, "close handshake timed out");
+    assert!(!reason.was_clean);
+    assert_eq!(reason.initiator, CloseInitiator::Local);
+
+    drop(socket);
+}
+
+#[wasm_bindgen_test]
+async fn close_timeout_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    close_timeout(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn close_timeout_standard() {
+    close_timeout(Some(Interface::Standard)).await;
+}
+
+async fn state(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+    assert_eq!(socket.state(), ConnectionState::Open);
+
+    let (sender, receiver) = socket.into_split();
+    assert_eq!(sender.state(), ConnectionState::Open);
+    assert_eq!(receiver.state(), ConnectionState::Open);
+
+    let mut changes = receiver.state_changes();
+    assert_eq!(changes.next().await, Some(ConnectionState::Open));
+
+    log!("Closing");
+    sender.close();
+    assert_eq!(receiver.state(), ConnectionState::Closing);
+
+    let states: Vec<_> = changes.collect().await;
+    log!("State changes: {states:?}");
+    assert!(states.is_sorted());
+    assert_eq!(states.last(), Some(&ConnectionState::Closed));
+    assert_eq!(receiver.state(), ConnectionState::Closed);
+}
+
+#[wasm_bindgen_test]
+async fn state_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    state(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn state_standard() {
+    state(Some(Interface::Standard)).await;
+}
+
+async fn connection_info(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    let info = socket.connection_info().clone();
+    log!("Connected: {info:?}");
+
+    assert!(info.url().starts_with(&url));
+    assert_eq!(info.protocol(), "");
+    assert_eq!(info.has_extension("permessage-deflate"), info.extensions().contains("permessage-deflate"));
+    if let Some(interface) = interface {
+        assert_eq!(info.interface(), interface);
+    }
+    assert!(info.opened_at() >= info.connect_started_at());
+    log!("Handshake took {:?}", info.handshake_duration());
+
+    let (sender, receiver) = socket.into_split();
+    assert_eq!(sender.connection_info().url(), info.url());
+    assert_eq!(receiver.connection_info().opened_at(), info.opened_at());
+}
+
+#[wasm_bindgen_test]
+async fn connection_info_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    connection_info(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn connection_info_standard() {
+    connection_info(Some(Interface::Standard)).await;
+}
+
+async fn close_gracefully(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let sent: Vec<_> = (0..10).map(|i| Msg::Text(format!("msg {i}"))).collect();
+    for msg in &sent {
+        socket.send(msg.clone()).await.unwrap_log();
+    }
+
+    log!("Closing gracefully");
+    let mut closing = socket.close_gracefully(CloseCode::NormalClosure, "Goodbye!");
+    let mut recved = Vec::new();
+    while let Some(msg) = closing.next().await {
+        recved.push(msg.unwrap_log());
+    }
+    log!("Received {} messages after closing", recved.len());
+    assert_eq!(recved[..], sent[..recved.len()]);
+
+    let reason = closing.await;
+    log!("Closed: {reason:?}");
+    assert_eq!(reason.code, CloseCode::NormalClosure);
+    assert!(reason.was_clean);
+}
+
+#[wasm_bindgen_test]
+async fn close_gracefully_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    close_gracefully(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn close_gracefully_standard() {
+    close_gracefully(Some(Interface::Standard)).await;
+}
+
+async fn close_handle(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.clone().connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let (_sender, mut receiver) = socket.into_split();
+    let handle = receiver.close_handle();
+    assert!(!handle.is_closed_locally());
+
+    log!("Closing using handle");
+    assert!(handle.clone().close_with_reason(CloseCode::Other(3001), "protocol violation"));
+    assert!(!handle.close());
+    assert!(handle.is_closed_locally());
+    while let Some(Ok(_)) = receiver.next().await {}
+    let reason = handle.closed().await;
+    log!("Closed: {reason:?}");
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let handle = socket.close_handle();
+    socket.send("CLOSE").await.unwrap_log();
+    let reason = socket.closed().await;
+    log!("Closed remotely: {reason:?}");
+    assert!(!handle.close());
+    assert!(!handle.is_closed_locally());
+}
+
+#[wasm_bindgen_test]
+async fn close_handle_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    close_handle(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn close_handle_standard() {
+    close_handle(Some(Interface::Standard)).await;
+}
+
+async fn close_handle_drop(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let handle = socket.close_handle();
+    assert!(!handle.is_closed_locally());
+
+    log!("Dropping WebSocket while handle is alive");
+    drop(socket);
+    assert!(handle.is_closed_locally());
+    assert!(!handle.close());
+
+    let reason = handle.closed().await;
+    log!("Closed: {reason:?}");
+    assert!(reason.was_clean, "server did not complete close handshake");
+    assert_eq!(reason.initiator, CloseInitiator::Local);
+}
+
+#[wasm_bindgen_test]
+async fn close_handle_drop_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    close_handle_drop(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn close_handle_drop_standard() {
+    close_handle_drop(Some(Interface::Standard)).await;
+}
+
+async fn reunite(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket1 = builder.clone().connect().await.expect_log("connect failed");
+    let socket2 = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket1:?} and {socket2:?}");
+
+    let (mut sender1, mut receiver1) = socket1.into_split();
+    let (sender2, receiver2) = socket2.into_split();
+
+    log!("Reuniting mismatched halves");
+    let ReuniteError(sender1_back, receiver2_back) =
+        sender1.reunite(receiver2).expect_err("reuniting mismatched halves must fail");
+    assert!(sender2.reunite(receiver2_back).is_ok());
+    sender1 = sender1_back;
+
+    sender1.send("hello").await.unwrap_log();
+    let mut buf = [0; 2];
+    receiver1.read_exact(&mut buf).await.unwrap_log();
+    assert_eq!(&buf, b"he");
+
+    log!("Reuniting matching halves");
+    let mut socket1 = sender1.reunite(receiver1).unwrap_log();
+    let mut buf = [0; 3];
+    socket1.read_exact(&mut buf).await.unwrap_log();
+    assert_eq!(&buf, b"llo");
+
+    socket1.send("world").await.unwrap_log();
+    assert_eq!(socket1.next().await.unwrap_log().unwrap_log(), Msg::Text("world".to_string()));
+}
+
+#[wasm_bindgen_test]
+async fn reunite_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    reunite(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn reunite_standard() {
+    reunite(Some(Interface::Standard)).await;
+}
+
+async fn shared_sender(interface: Option<Interface>) {
+    const PRODUCERS: usize = 3;
+    const MSGS: usize = 50;
+
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let (sender, mut receiver) = socket.into_split();
+    let sender = sender.into_shared();
+
+    for p in 0..PRODUCERS {
+        let sender = sender.clone();
+        spawn_local(async move {
+            for n in 0..MSGS {
+                sender.send(Msg::Text(format!("{p} {n}"))).await.unwrap_log();
+            }
+        });
+    }
+
+    let mut next = [0; PRODUCERS];
+    for _ in 0..PRODUCERS * MSGS {
+        let msg = receiver.next().await.unwrap_log().unwrap_log().to_string();
+        let (p, n) = msg.split_once(' ').unwrap();
+        let (p, n): (usize, usize) = (p.parse().unwrap(), n.parse().unwrap());
+        assert_eq!(n, next[p], "message of producer {p} out of order");
+        next[p] += 1;
+    }
+    assert_eq!(next, [MSGS; PRODUCERS]);
+
+    assert!(sender.close_handle().close());
+}
+
+#[wasm_bindgen_test]
+async fn shared_sender_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    shared_sender(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn shared_sender_standard() {
+    shared_sender(Some(Interface::Standard)).await;
+}
+
+async fn error(interface: Option<Interface>) {
+    let mut builder = WebSocketBuilder::new("not a url");
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to invalid URL using {interface:?}");
+    match builder.connect().await {
+        Err(err @ WebSocketError::InvalidUrl(_)) => {
+            log!("Connect failed: {err}");
+            let err = io::Error::from(err);
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            assert!(matches!(
+                err.get_ref().and_then(|err| err.downcast_ref::<WebSocketError>()),
+                Some(WebSocketError::InvalidUrl(_))
+            ));
+        }
+        other => panic_log!("expected invalid URL, but got {other:?}"),
+    }
+
+    let mut builder = WebSocketBuilder::new("ws://127.0.0.1:1");
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to closed port using {interface:?}");
+    match builder.connect().await {
+        Err(err @ WebSocketError::ConnectFailed(_)) => {
+            log!("Connect failed: {err}");
+            assert_eq!(io::Error::from(err).kind(), ErrorKind::ConnectionRefused);
+        }
+        other => panic_log!("expected connect failure, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn error_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    error(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn error_standard() {
+    error(Some(Interface::Standard)).await;
+}
+
+async fn receive_closed(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    log!("Dropping connection on server");
+    socket.send("DROP").await.unwrap_log();
+    match socket.read_u8().await {
+        Err(err) => {
+            log!("Read failed: {err}");
+            assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+            let reason = err.get_ref().and_then(|err| err.downcast_ref::<ClosedReason>()).expect_log("no reason");
+            log!("Close reason: {reason:?}");
+            assert_eq!(reason.code, CloseCode::AbnormalClosure);
+            assert!(!reason.was_clean);
+        }
+        Ok(data) => panic_log!("expected read to fail, but got {data}"),
+    }
+
+    match socket.next().await {
+        Some(Err(WebSocketError::Closed(reason))) => {
+            log!("Receive failed: {reason:?}");
+            assert_eq!(reason.code, CloseCode::AbnormalClosure);
+            assert!(!reason.was_clean);
+        }
+        other => panic_log!("expected close reason, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn receive_closed_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    receive_closed(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn receive_closed_standard() {
+    receive_closed(Some(Interface::Standard)).await;
+}
+
+async fn close_initiator(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.clone().connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    socket.send("CLOSE-remote").await.unwrap_log();
+    let reason = socket.closed().await;
+    log!("Closed by server: {reason:?}");
+    assert_eq!(reason.code, CloseCode::Other(3999));
+    assert_eq!(reason.reason, "CLOSE-remote");
+    assert!(reason.was_clean);
+    assert_eq!(reason.initiator, CloseInitiator::Remote);
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.clone().connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let closed = socket.closed();
+    socket.close_with_reason(CloseCode::Other(4000), "local");
+    let reason = closed.await;
+    log!("Closed locally: {reason:?}");
+    assert_eq!(reason.code, CloseCode::Other(4000));
+    assert!(reason.was_clean);
+    assert_eq!(reason.initiator, CloseInitiator::Local);
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    socket.send("DROP").await.unwrap_log();
+    let reason = socket.closed().await;
+    log!("Dropped by server: {reason:?}");
+    assert_eq!(reason.code, CloseCode::AbnormalClosure);
+    assert!(!reason.was_clean);
+    assert_eq!(reason.initiator, CloseInitiator::Browser);
+}
+
+#[wasm_bindgen_test]
+async fn close_initiator_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    close_initiator(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn close_initiator_standard() {
+    close_initiator(Some(Interface::Standard)).await;
+}
+
+async fn receive_limits(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_max_message_size(100);
+
+    log!("Connecting to {url} using {interface:?} with maximum message size");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    socket.send(vec![1; 100]).await.unwrap_log();
+    let recved = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(recved, Msg::Binary(vec![1; 100]));
+
+    socket.send(vec![2; 101]).await.unwrap_log();
+    match socket.next().await {
+        Some(Err(WebSocketError::Closed(reason))) => {
+            log!("Receive failed: {reason:?}");
+            assert_eq!(reason.code, CloseCode::MessageTooBig);
+            assert_eq!(reason.initiator, CloseInitiator::Local);
+        }
+        other => panic_log!("expected message size violation, but got {other:?}"),
+    }
+    assert_eq!(socket.closed().await.code, CloseCode::MessageTooBig);
+
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_max_message_rate(1, 5);
+
+    log!("Connecting to {url} using {interface:?} with maximum message rate");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    for i in 0..10 {
+        socket.send(format!("{i}")).await.unwrap_log();
+    }
+    for i in 0..5 {
+        let recved = socket.next().await.unwrap_log().unwrap_log();
+        assert_eq!(recved, Msg::Text(format!("{i}")));
+    }
+    match socket.next().await {
+        Some(Err(WebSocketError::Closed(reason))) => {
+            log!("Receive failed: {reason:?}");
+            assert_eq!(reason.code, CloseCode::PolicyViolation);
+        }
+        other => panic_log!("expected message rate violation, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn receive_limits_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    receive_limits(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn receive_limits_standard() {
+    receive_limits(Some(Interface::Standard)).await;
+}
+
+async fn receive_overflow(policy: ReceiveOverflowPolicy, sent: &[&str], expected: &[&str]) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    builder.set_interface(Interface::Standard);
+    builder.set_receive_buffer_messages(2);
+    builder.set_receive_overflow_policy(policy.clone());
+
+    log!("Connecting to {url} using {policy:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    for msg in sent {
+        socket.send(*msg).await.unwrap_log();
+    }
+    sleep(Duration::from_millis(500)).await;
+
+    for msg in expected {
+        let recved = socket.next().await.unwrap_log().unwrap_log();
+        assert_eq!(recved, Msg::Text(msg.to_string()));
+    }
+    log!("Dropped {} messages", socket.dropped_messages());
+    assert_eq!(socket.dropped_messages(), (sent.len() - expected.len()) as u64);
+
+    socket.send("last").await.unwrap_log();
+    let recved = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(recved, Msg::Text("last".to_string()));
+}
+
+#[wasm_bindgen_test]
+async fn receive_overflow_standard() {
+    let sent = ["a1", "b1", "a2", "b2", "a3"];
+    receive_overflow(ReceiveOverflowPolicy::DropNewest, &sent, &["a1", "b1"]).await;
+    receive_overflow(ReceiveOverflowPolicy::DropOldest, &sent, &["b2", "a3"]).await;
+    receive_overflow(
+        ReceiveOverflowPolicy::coalesce(|msg| msg.to_string().get(..1).map(|key| key.to_string())),
+        &sent,
+        &["b2", "a3"],
+    )
+    .await;
+    receive_overflow(
+        ReceiveOverflowPolicy::coalesce(|msg| (msg.to_string() != "b1").then(|| "key".to_string())),
+        &sent,
+        &["b1", "a3"],
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+async fn receive_overflow_close_standard() {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    builder.set_interface(Interface::Standard);
+    builder.set_receive_buffer_messages(2);
+
+    log!("Connecting to {url}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    for msg in ["a", "b", "c"] {
+        socket.send(msg).await.unwrap_log();
+    }
+    sleep(Duration::from_millis(500)).await;
+
+    loop {
+        match socket.next().await {
+            Some(Ok(msg)) => {
+                log!("Received {msg:?}");
+            }
+            Some(Err(WebSocketError::ReceiveOverflow(reason))) => {
+                log!("Receive overflow: {reason:?}");
+                assert_eq!(reason.code, CloseCode::MessageTooBig);
+                assert_eq!(reason.initiator, CloseInitiator::LocalOverflow);
+
+                let err = io::Error::from(WebSocketError::ReceiveOverflow(reason));
+                assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+                assert!(err.get_ref().unwrap().downcast_ref::<ClosedReason>().is_some());
+                break;
+            }
+            other => panic_log!("expected receive overflow, but got {other:?}"),
+        }
+    }
+}
+
+async fn receive_overflow_credits(policy: ReceiveOverflowPolicy) {
+    const MSG_SIZE: usize = 4096;
+    const WINDOW: usize = 16;
+
+    let host = web_sys::window().unwrap().location().hostname().unwrap();
+    let url = format!("ws://{host}:8766");
+    let mut builder = WebSocketBuilder::new(&url);
+    builder.set_interface(Interface::Standard);
+    builder.set_flow_control(FlowControl::new(WINDOW * MSG_SIZE, WINDOW));
+    builder.set_receive_buffer_messages(2);
+    builder.set_receive_overflow_policy(policy.clone());
+
+    log!("Connecting to {url} using {policy:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+    socket.send("recv-credit").await.unwrap_log();
+
+    // The server stalls unless credits for dropped messages are returned.
+    sleep(Duration::from_millis(500)).await;
+    let receive = async {
+        for _ in 0..10 * WINDOW {
+            let msg = socket.next().await.unwrap_log().unwrap_log();
+            assert_eq!(msg.len(), MSG_SIZE);
+        }
+    };
+    match select(pin!(receive), pin!(sleep(Duration::from_secs(10)))).await {
+        Either::Left(((), _)) => (),
+        Either::Right(((), _)) => panic_log!("server stalled because of missing credits"),
+    }
+
+    log!("Dropped {} messages", socket.dropped_messages());
+    assert!(socket.dropped_messages() > 0);
+}
+
+#[wasm_bindgen_test]
+async fn receive_overflow_credits_standard() {
+    receive_overflow_credits(ReceiveOverflowPolicy::DropNewest).await;
+    receive_overflow_credits(ReceiveOverflowPolicy::DropOldest).await;
+}
+
+async fn pause(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_read_prefetch(false);
+    builder.set_receive_timeout(Duration::from_millis(300));
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+    let (mut tx, mut rx) = socket.into_split();
+
+    tx.send("a").await.unwrap_log();
+    assert_eq!(rx.next().await.unwrap_log().unwrap_log(), Msg::Text("a".to_string()));
+
+    log!("Pausing");
+    rx.pause();
+    assert!(rx.is_paused());
+    tx.send("b").await.unwrap_log();
+    tx.send("c").await.unwrap_log();
+    match select(rx.next(), pin!(sleep(Duration::from_millis(500)))).await {
+        Either::Left((msg, _)) => panic_log!("expected no message while paused, but got {msg:?}"),
+        Either::Right(_) => {
+            log!("No message received while paused");
+        }
+    }
+
+    log!("Resuming");
+    rx.resume();
+    assert!(!rx.is_paused());
+    assert_eq!(rx.next().await.unwrap_log().unwrap_log(), Msg::Text("b".to_string()));
+    assert_eq!(rx.next().await.unwrap_log().unwrap_log(), Msg::Text("c".to_string()));
+}
+
+#[wasm_bindgen_test]
+async fn pause_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    pause(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn pause_standard() {
+    pause(Some(Interface::Standard)).await;
+}
+
+async fn recv_many(interface: Option<Interface>) {
+    const CNT: usize = 10;
+
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    for i in 0..CNT {
+        socket.send(format!("{i}")).await.unwrap_log();
+    }
+    sleep(Duration::from_millis(200)).await;
+
+    let mut msgs = Vec::new();
+    assert_eq!(socket.recv_many(&mut msgs, 0).await.unwrap_log(), 0);
+    while msgs.len() < CNT {
+        let n = socket.recv_many(&mut msgs, 4).await.unwrap_log();
+        log!("Received {n} messages");
+        assert!((1..=4).contains(&n));
+    }
+    let expected: Vec<_> = (0..CNT).map(|i| Msg::Text(format!("{i}"))).collect();
+    assert_eq!(msgs, expected);
+
+    socket.send("CLOSE").await.unwrap_log();
+    assert_eq!(socket.recv_many(&mut msgs, 4).await.unwrap_log(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn recv_many_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    recv_many(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn recv_many_standard() {
+    recv_many(Some(Interface::Standard)).await;
+}
+
+async fn try_recv(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    assert_eq!(socket.try_recv().unwrap_log(), None);
+    for i in 0..3 {
+        socket.send(format!("{i}")).await.unwrap_log();
+    }
+    sleep(Duration::from_millis(200)).await;
+
+    while socket.peek().unwrap_log().is_none() {
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(socket.peek().unwrap_log(), Some(&Msg::Text("0".to_string())));
+    log!("Queued {} messages of {} bytes", socket.queued_len(), socket.queued_bytes());
+    match socket.interface() {
+        Interface::Stream => {
+            assert_eq!(socket.queued_len(), 1);
+            assert_eq!(socket.queued_bytes(), 1);
+        }
+        Interface::Standard => {
+            assert_eq!(socket.queued_len(), 3);
+            assert_eq!(socket.queued_bytes(), 3);
+        }
+    }
+
+    for i in 0..3 {
+        let msg = loop {
+            if let Some(msg) = socket.try_recv().unwrap_log() {
+                break msg;
+            }
+            sleep(Duration::from_millis(10)).await;
+        };
+        assert_eq!(msg, Msg::Text(format!("{i}")));
+    }
+    assert_eq!(socket.queued_len(), 0);
+
+    socket.send("CLOSE").await.unwrap_log();
+    let reason = loop {
+        match socket.try_recv() {
+            Ok(None) => sleep(Duration::from_millis(10)).await,
+            Err(WebSocketError::Closed(reason)) => break reason,
+            other => panic_log!("expected close, but got {other:?}"),
+        }
+    };
+    log!("Closed: {reason:?}");
+    assert_eq!(reason.code, CloseCode::Other(3999));
+    assert!(reason.was_clean);
+}
+
+#[wasm_bindgen_test]
+async fn try_recv_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    try_recv(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn try_recv_standard() {
+    try_recv(Some(Interface::Standard)).await;
+}
+
+async fn recv_into(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let data: Vec<u8> = (0..100).collect();
+    socket.send(data.clone()).await.unwrap_log();
+    socket.send("text").await.unwrap_log();
+
+    let mut small = [0; 10];
+    match socket.recv_into(&mut small).await {
+        Err(WebSocketError::BufferTooSmall { required }) => assert_eq!(required, data.len()),
+        other => panic_log!("expected buffer too small, but got {other:?}"),
+    }
+
+    let mut buf = [0; 200];
+    let len = socket.recv_into(&mut buf).await.unwrap_log().unwrap();
+    assert_eq!(&buf[..len], &data);
+
+    let mut vec = Vec::with_capacity(1);
+    let len = socket.recv_into_vec(&mut vec).await.unwrap_log().unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(vec, b"text");
+
+    // Read partially via AsyncRead.
+    socket.send(data.clone()).await.unwrap_log();
+    let mut part = [0; 30];
+    socket.read_exact(&mut part).await.unwrap_log();
+    assert_eq!(&part, &data[..30]);
+    let mut rest = [0; 70];
+    socket.read_exact(&mut rest).await.unwrap_log();
+    assert_eq!(&rest, &data[30..]);
+
+    socket.send("CLOSE").await.unwrap_log();
+    assert_eq!(socket.recv_into_vec(&mut vec).await.unwrap_log(), None);
+}
+
+#[wasm_bindgen_test]
+async fn recv_into_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    recv_into(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn recv_into_standard() {
+    recv_into(Some(Interface::Standard)).await;
+}
+
+async fn recv_js(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_receive_mode(ReceiveMode::Js);
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    socket.send("text").await.unwrap_log();
+    socket.send(vec![1, 2, 3]).await.unwrap_log();
+    socket.send("converted").await.unwrap_log();
+
+    let msg = socket.recv_js().await.unwrap_log().unwrap();
+    assert!(matches!(msg, JsMsg::Text(_)));
+    assert_eq!(JsValue::from(msg.clone()).as_string().as_deref(), Some("text"));
+    assert_eq!(msg.to_msg(), Msg::Text("text".to_string()));
+
+    let msg = socket.recv_js().await.unwrap_log().unwrap();
+    assert!(matches!(msg, JsMsg::ArrayBuffer(_)));
+    assert_eq!(msg.to_msg(), Msg::Binary(vec![1, 2, 3]));
+
+    let msg = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(msg, Msg::Text("converted".to_string()));
+
+    socket.send("CLOSE").await.unwrap_log();
+    assert!(socket.recv_js().await.unwrap_log().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn recv_js_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    recv_js(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn recv_js_standard() {
+    recv_js(Some(Interface::Standard)).await;
+}
+
+#[wasm_bindgen_test]
+async fn receive_blobs_standard() {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    builder.set_interface(Interface::Standard);
+    builder.set_receive_blobs(true);
+
+    log!("Connecting to {url}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let data: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+    for _ in 0..3 {
+        socket.send(data.clone()).await.unwrap_log();
+    }
+
+    let Some(JsMsg::Blob(blob)) = socket.recv_js().await.unwrap_log() else { panic_log!("expected blob") };
+    let mut reader = BlobReader::new(blob);
+    assert_eq!(reader.len(), data.len() as u64);
+    reader.set_chunk_size(1000);
+    let mut chunks = Vec::new();
+    while let Some(chunk) = reader.next().await {
+        chunks.push(chunk.unwrap_log());
+    }
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks.concat(), data);
+
+    let mut buf = vec![0; data.len()];
+    socket.read_exact(&mut buf).await.unwrap_log();
+    assert_eq!(buf, data);
+
+    let msg = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(msg, Msg::Binary(data));
+
+    socket.close();
+}
+
+#[wasm_bindgen_test]
+async fn recv_into_cancel_standard() {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    builder.set_interface(Interface::Standard);
+    builder.set_receive_blobs(true);
+
+    log!("Connecting to {url}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let data: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+    socket.send(data.clone()).await.unwrap_log();
+    while socket.queued_len() == 0 {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    log!("Dropping receive while reading blob");
+    let mut vec = Vec::new();
+    assert!(socket.recv_into_vec(&mut vec).now_or_never().is_none());
+    assert_eq!(socket.queued_len(), 1);
+
+    let mut buf = vec![0; data.len()];
+    let len = socket.recv_into(&mut buf).await.unwrap_log().unwrap();
+    assert_eq!(&buf[..len], &data);
+
+    socket.close();
+}
+
+#[cfg(feature = "bytes")]
+async fn bytes(interface: Option<Interface>) {
+    use bytes::{Bytes, BytesMut};
+
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let payload = Bytes::from_static(b"shared payload");
+    socket.send(payload.clone()).await.unwrap_log();
+    socket.send(BytesMut::from(&b"mutable"[..])).await.unwrap_log();
+    socket.send("text").await.unwrap_log();
+
+    assert_eq!(socket.recv_bytes().await.unwrap_log(), Some(payload.clone()));
+    assert_eq!(socket.recv_bytes().await.unwrap_log(), Some(Bytes::from_static(b"mutable")));
+    let msg = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(Bytes::from(msg), Bytes::from_static(b"text"));
+    assert_eq!(Msg::from(payload.clone()), Msg::Binary(payload.to_vec()));
+
+    socket.send("CLOSE").await.unwrap_log();
+    assert_eq!(socket.recv_bytes().await.unwrap_log(), None);
+}
+
+#[cfg(feature = "bytes")]
+#[wasm_bindgen_test]
+async fn bytes_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    bytes(Some(Interface::Stream)).await;
+}
+
+#[cfg(feature = "bytes")]
+#[wasm_bindgen_test]
+async fn bytes_standard() {
+    bytes(Some(Interface::Standard)).await;
+}
+
+async fn send_owned(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    builder.set_send_buffer_size(65_536);
+
+    log!("Connecting to {url} using {interface:?}");
+    let socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+    let (mut tx, mut rx) = socket.into_split();
+
+    // Owned buffers, borrowed slices and text are interleaved to check that held back
+    // owned buffers are sent in order.
+    let msg = |i: usize| vec![i as u8; 1000 + i];
+    spawn_local(async move {
+        for i in 0..300 {
+            match i % 3 {
+                0 => tx.send(msg(i)).await.unwrap_log(),
+                1 => tx.send(msg(i).as_slice()).await.unwrap_log(),
+                _ => tx.send(format!("{i}")).await.unwrap_log(),
+            }
+        }
+    });
+
+    for i in 0..300 {
+        let recved = rx.next().await.unwrap_log().unwrap_log();
+        match i % 3 {
+            0 | 1 => assert_eq!(recved, Msg::Binary(msg(i))),
+            _ => assert_eq!(recved, Msg::Text(format!("{i}"))),
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn send_owned_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    send_owned(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn send_owned_standard() {
+    send_owned(Some(Interface::Standard)).await;
+}
+
+async fn send_js(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(&b"blob"[..]));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).unwrap();
+    socket.send(JsMsg::from(js_sys::JsString::from("text"))).await.unwrap_log();
+    socket.send(JsMsg::from(blob)).await.unwrap_log();
+    socket.send(JsMsg::from(js_sys::Uint8Array::from(&b"array"[..]))).await.unwrap_log();
+    socket.send(JsMsg::from(js_sys::Uint8Array::from(&b"buffer"[..]).buffer())).await.unwrap_log();
+
+    assert_eq!(socket.next().await.unwrap_log().unwrap_log(), Msg::Text("text".to_string()));
+    assert_eq!(socket.next().await.unwrap_log().unwrap_log(), Msg::Binary(b"blob".to_vec()));
+    assert_eq!(socket.next().await.unwrap_log().unwrap_log(), Msg::Binary(b"array".to_vec()));
+    assert_eq!(socket.next().await.unwrap_log().unwrap_log(), Msg::Binary(b"buffer".to_vec()));
+
+    socket.close();
+}
+
+#[wasm_bindgen_test]
+async fn send_js_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    send_js(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn send_js_standard() {
+    send_js(Some(Interface::Standard)).await;
+}
+
+async fn heartbeat(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    let ping = Msg::Text("PING".to_string());
+    builder.set_heartbeat(Heartbeat::new(
+        Duration::from_millis(100),
+        Duration::from_millis(500),
+        ping.clone(),
+        move |msg| *msg == ping,
+    ));
+
+    log!("Connecting to {url} using {interface:?}");
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    for i in 0..10 {
+        let msg = format!("msg {i}");
+        socket.send(msg.as_str()).await.unwrap_log();
+        let recved = socket.next().await.unwrap_log().unwrap_log();
+        assert_eq!(recved, Msg::Text(msg));
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    log!("Muting server");
+    socket.send("MUTE").await.unwrap_log();
+    match socket.next().await {
+        Some(Err(err @ WebSocketError::TimedOut)) => {
+            log!("Heartbeat timed out: {err}");
+        }
+        other => panic_log!("expected heartbeat timeout, but got {other:?}"),
+    }
+
+    let reason = socket.closed().await;
+    log!("Closed: {reason:?}");
+    assert_eq!(reason.code, CloseCode::AbnormalClosure);
+    assert!(!reason.was_clean);
+    assert_eq!(reason.initiator, CloseInitiator::Local);
+}
+
+#[wasm_bindgen_test]
+async fn heartbeat_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    heartbeat(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn heartbeat_standard() {
+    heartbeat(Some(Interface::Standard)).await;
+}
+
+async fn abort(interface: Option<Interface>) {
+    let url = url();
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+
+    log!("Connecting to {url} using {interface:?} with aborted signal");
+    let handle = AbortHandle::new();
+    let mut aborted_builder = builder.clone();
+    aborted_builder.set_abort_signal(handle.signal());
+    handle.abort();
+    match aborted_builder.connect().await {
+        Err(err @ WebSocketError::Aborted) => {
+            log!("Connect aborted: {err}");
+        }
+        other => panic_log!("expected connect to be aborted, but got {other:?}"),
+    }
+
+    log!("Connecting to {url} using {interface:?}");
+    let handle = AbortHandle::new();
+    builder.set_abort_signal(handle.signal());
+    let mut socket = builder.connect().await.expect_log("connect failed");
+    log!("Connected: {socket:?}");
+
+    socket.send("hi").await.unwrap_log();
+    let recved = socket.next().await.unwrap_log().unwrap_log();
+    assert_eq!(recved, Msg::Text("hi".to_string()));
+
+    log!("Aborting");
+    handle.abort();
+    match socket.next().await {
+        Some(Err(err @ WebSocketError::Aborted)) => {
+            log!("Receive aborted: {err}");
+        }
+        other => panic_log!("expected receive to be aborted, but got {other:?}"),
+    }
+}
+
+async fn abort_connecting(interface: Option<Interface>) {
+    let host = web_sys::window().unwrap().location().hostname().unwrap();
+    let url = format!("ws://{host}:8767");
+    let mut builder = WebSocketBuilder::new(&url);
+    if let Some(interface) = interface {
+        builder.set_interface(interface);
+    }
+    let handle = AbortHandle::new();
+    builder.set_abort_signal(handle.signal());
+
+    spawn_local(async move {
+        sleep(Duration::from_millis(500)).await;
+        log!("Aborting");
+        handle.abort();
+    });
+
+    log!("Connecting to unresponsive {url} using {interface:?}");
+    match builder.connect().await {
+        Err(err @ WebSocketError::Aborted) => {
+            log!("Connect aborted: {err}");
+        }
+        other => panic_log!("expected connect to be aborted, but got {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn abort_connecting_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    abort_connecting(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn abort_connecting_standard() {
+    abort_connecting(Some(Interface::Standard)).await;
+}
+
+#[wasm_bindgen_test]
+async fn abort_stream() {
+    if !Interface::Stream.is_supported() {
+        log!("WebSocketStream not supported");
+        return;
+    }
+    abort(Some(Interface::Stream)).await;
+}
+
+#[wasm_bindgen_test]
+async fn abort_standard() {
+    abort(Some(Interface::Standard)).await;
+}
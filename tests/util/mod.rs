@@ -1,6 +1,7 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
 
 /// Log to console.
 #[macro_export]
@@ -97,3 +98,15 @@ pub fn write_msg(msg: &str) {
     message.set_inner_html(&format!("<pre>{msg}</pre>"));
     body.append_child(&message).unwrap();
 }
+
+/// Sleep for the specified duration.
+#[allow(dead_code)]
+pub async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, duration.as_millis() as i32)
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}